@@ -0,0 +1,147 @@
+// arithmetic and comparisons on atoms
+
+use std::cmp::Ordering;
+use crate::error::MerlinError;
+
+// our boolean atoms
+
+pub const TRUE: &str = "true";
+pub const FALSE: &str = "false";
+
+// a number, kept as an integer whenever possible
+
+#[derive(Copy, Clone)]
+enum Number {
+	Integer(i64),
+	Float(f64),
+}
+
+impl Number {
+	fn float(self) -> f64 {
+		match self {
+			Number::Integer(i) => i as f64,
+			Number::Float(f)   => f,
+		}
+	}
+
+	fn is_zero(self) -> bool {
+		self.float() == 0.0
+	}
+
+	fn cmp(self, other: Number) -> Ordering {
+		match (self, other) {
+			(Number::Integer(x), Number::Integer(y)) => x.cmp(&y),
+			(x, y)                                   => x.float().partial_cmp(&y.float()).unwrap_or(Ordering::Equal),
+		}
+	}
+}
+
+// parse an atom into a number, following the same rules as positional arguments
+
+fn number(s: &str) -> Result<Number, MerlinError> {
+	let s = s.trim();
+
+	if let Ok(i) = s.parse::<i64>() {
+		return Ok(Number::Integer(i));
+	}
+
+	match s.parse::<f64>() {
+		Ok(f) if f.is_finite() => Ok(Number::Float(f)),
+		_                      => Err(MerlinError::NotANumber),
+	}
+}
+
+// make an arithmetic command, using checked integer math and falling back on floats
+
+macro_rules! make_arithmetic {
+	($name:ident, $checked:ident, $op:tt, $divides:expr) => {
+		pub fn $name(a: &str, b: &str) -> Result<String, MerlinError> {
+			let (x, y) = (number(a)?, number(b)?);
+
+			if $divides && y.is_zero() {
+				return Err(MerlinError::DivideByZero);
+			}
+
+			match (x, y) {
+				(Number::Integer(x), Number::Integer(y)) => x.$checked(y)
+					.map(|n| n.to_string())
+					.ok_or(MerlinError::Overflow),
+				(x, y)                                   => Ok((x.float() $op y.float()).to_string()),
+			}
+		}
+	}
+}
+
+make_arithmetic!(add, checked_add, +, false);
+make_arithmetic!(subtract, checked_sub, -, false);
+make_arithmetic!(multiply, checked_mul, *, false);
+make_arithmetic!(divide, checked_div, /, true);
+make_arithmetic!(modulo, checked_rem, %, true);
+
+// return the smaller or larger of two numbers
+
+pub fn min(a: &str, b: &str) -> Result<String, MerlinError> {
+	pick(a, b, Ordering::Less)
+}
+
+pub fn max(a: &str, b: &str) -> Result<String, MerlinError> {
+	pick(a, b, Ordering::Greater)
+}
+
+fn pick(a: &str, b: &str, wanted: Ordering) -> Result<String, MerlinError> {
+	let (x, y) = (number(a)?, number(b)?);
+
+	if y.cmp(x) == wanted {
+		Ok(b.trim().to_string())
+	} else {
+		Ok(a.trim().to_string())
+	}
+}
+
+// make a comparison command, pushing a boolean atom
+
+macro_rules! make_comparison {
+	($name:ident, $($ordering:pat_param)|+) => {
+		pub fn $name(a: &str, b: &str) -> Result<String, MerlinError> {
+			Ok(boolean(matches!(number(a)?.cmp(number(b)?), $($ordering)|+)).to_string())
+		}
+	}
+}
+
+make_comparison!(less, Ordering::Less);
+make_comparison!(greater, Ordering::Greater);
+make_comparison!(less_equal, Ordering::Less | Ordering::Equal);
+make_comparison!(greater_equal, Ordering::Greater | Ordering::Equal);
+
+// check if two atoms are equal, comparing as text when they aren't both numbers
+
+pub fn equal(a: &str, b: &str) -> String {
+	let same = match (number(a), number(b)) {
+		(Ok(x), Ok(y)) => x.cmp(y) == Ordering::Equal,
+		_              => a == b,
+	};
+
+	boolean(same).to_string()
+}
+
+pub fn unequal(a: &str, b: &str) -> String {
+	boolean(equal(a, b) == FALSE).to_string()
+}
+
+// check if an atom counts as true: anything but "false", zero or blank
+
+pub fn truthy(atom: &str) -> bool {
+	match number(atom) {
+		Ok(n)  => !n.is_zero(),
+		Err(_) => !(atom.trim().is_empty() || atom == FALSE),
+	}
+}
+
+// convert a bool into a boolean atom
+
+pub fn boolean(b: bool) -> &'static str {
+	match b {
+		true  => TRUE,
+		false => FALSE,
+	}
+}
@@ -77,7 +77,7 @@ fn make_command(script: &str, stdin: Stdio, stdout: Stdio, stderr: Stdio) -> Res
 	let args: Vec<&str> = script.split_whitespace().collect();
 
 	if !args.is_empty() { // don't attempt to run if the command name is a blank string
-		Command::new(&args[0])
+		Command::new(args[0])
 			.args(&args[1..])
 			.envs(env::vars_os())
 			.stdin(stdin)
@@ -86,6 +86,6 @@ fn make_command(script: &str, stdin: Stdio, stdout: Stdio, stderr: Stdio) -> Res
 			.spawn()
 			.or(Err(MerlinError::InvalidExternal))
 	} else {
-		return Err(MerlinError::InvalidExternal);
+		Err(MerlinError::InvalidExternal)
 	}
 }
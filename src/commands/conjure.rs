@@ -0,0 +1,152 @@
+// run interactive external commands on a pseudo-terminal
+
+use crate::{error::MerlinError, terminal::{self, RawMode, STDIN, STDOUT}};
+
+use std::{
+	process::Command,
+	fs::File,
+	io::{self, Write},
+	os::unix::{io::{AsRawFd, FromRawFd, RawFd}, process::CommandExt},
+};
+
+use nix::{
+	fcntl::{fcntl, FcntlArg, FdFlag},
+	libc,
+	poll::{poll, PollFd, PollFlags},
+	pty::openpty,
+	unistd,
+};
+
+// how often (in milliseconds) we check if the terminal has been resized
+
+const RESIZE_INTERVAL: libc::c_int = 250;
+
+// run a command connected to the user's terminal through a pty, optionally returning a transcript of its output
+
+pub fn conjure(script: &str, record: bool) -> Result<String, MerlinError> {
+	let args: Vec<&str> = script.split_whitespace().collect();
+
+	if args.is_empty() { // don't attempt to run if the command name is a blank string
+		return Err(MerlinError::InvalidExternal);
+	}
+
+	let mut size = terminal::window_size(STDIN);
+
+	let pty = openpty(size.as_ref(), None)
+		.or(Err(MerlinError::InvalidExternal))?;
+
+	// the master end stays with us, don't leak it into the child
+
+	fcntl(pty.master, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))
+		.or(Err(MerlinError::InvalidExternal))?;
+
+	let master = unsafe { File::from_raw_fd(pty.master) };
+	let slave = unsafe { File::from_raw_fd(pty.slave) };
+
+	// spawn the child with the slave end as its controlling terminal
+
+	let mut child = {
+		let mut command = Command::new(args[0]);
+
+		command.args(&args[1..])
+			.stdin(slave.try_clone().or(Err(MerlinError::InvalidExternal))?)
+			.stdout(slave.try_clone().or(Err(MerlinError::InvalidExternal))?)
+			.stderr(slave);
+
+		unsafe {
+			command.pre_exec(|| {
+				unistd::setsid()?;
+
+				if libc::ioctl(libc::STDIN_FILENO, libc::TIOCSCTTY as _, 0) == -1 {
+					return Err(io::Error::last_os_error());
+				}
+
+				Ok(())
+			});
+		}
+
+		command.spawn()
+			.or(Err(MerlinError::InvalidExternal))?
+	}; // our copies of the slave end are dropped here, so we see EOF when the child exits
+
+	let _ = io::stdout().flush();
+
+	// only forward keystrokes when we are actually talking to a terminal
+
+	let raw = RawMode::enter(STDIN);
+	let transcript = relay(master.as_raw_fd(), raw.is_some(), &mut size, record);
+
+	drop(raw);
+
+	child.wait()
+		.or(Err(MerlinError::InvalidExternal))?;
+
+	Ok(String::from_utf8_lossy(&transcript).replace('\r', ""))
+}
+
+// shuffle bytes between the user's terminal and the pty until the child hangs up
+
+fn relay(master: RawFd, forward: bool, size: &mut Option<libc::winsize>, record: bool) -> Vec<u8> {
+	let mut transcript = Vec::new();
+	let mut buf = [0u8; 4096];
+
+	loop {
+		let mut fds = [
+			PollFd::new(master, PollFlags::POLLIN),
+			PollFd::new(STDIN, PollFlags::POLLIN),
+		];
+
+		let watched = if forward { 2 } else { 1 };
+
+		match poll(&mut fds[..watched], RESIZE_INTERVAL) {
+			Ok(_)                  => (),
+			Err(nix::Error::EINTR) => continue,
+			Err(_)                 => break,
+		}
+
+		resize(master, size);
+
+		// output from the child
+
+		if fds[0].revents().is_some_and(|r| !r.is_empty()) {
+			match unistd::read(master, &mut buf) {
+				Ok(0) | Err(_) => break, // EIO: every slave end has been closed
+				Ok(n)          => {
+					if terminal::write_all(STDOUT, &buf[..n]).is_err() {
+						break;
+					}
+
+					if record {
+						transcript.extend_from_slice(&buf[..n]);
+					}
+				}
+			}
+		}
+
+		// input from the user
+
+		if forward && fds[1].revents().is_some_and(|r| r.contains(PollFlags::POLLIN)) {
+			if let Ok(n) = unistd::read(STDIN, &mut buf) {
+				if terminal::write_all(master, &buf[..n]).is_err() {
+					break;
+				}
+			}
+		}
+	}
+
+	transcript
+}
+
+// pass changes in the size of our terminal on to the pty
+
+fn resize(master: RawFd, size: &mut Option<libc::winsize>) {
+	let current = terminal::window_size(STDIN);
+
+	if let (Some(new), Some(old)) = (current, *size) {
+		if new.ws_row != old.ws_row || new.ws_col != old.ws_col {
+			terminal::set_window_size(master, &new);
+		}
+	}
+
+	*size = current;
+}
@@ -1,7 +1,11 @@
 use std::str::FromStr;
 use crate::error::MerlinError;
 
+#[allow(clippy::module_inception)]
 pub mod commands;
+pub mod conjure;
+pub mod arithmetic;
+pub mod strings;
 
 // An enum that represents commands
 
@@ -54,66 +58,246 @@ pub enum Command {
 	Carve,
 	Spellbook,
 	Protean,
+	Wander,
+	Locus,
+	Imbue,
+	Purge,
+	Essence,
+	Conjure,
+	Chronicle,
+	Restrict,
+	Add,
+	Subtract,
+	Multiply,
+	Divide,
+	Modulo,
+	Min,
+	Max,
+	Equal,
+	Unequal,
+	Less,
+	Greater,
+	LessEqual,
+	GreaterEqual,
+	Heed,
+	Augur,
+	Chant,
+	Ritual,
+	Abyss,
+	Eternity,
+	Cast,
+	Dip,
+	Keep,
+	Transmute,
+	Dup,
+	Over,
+	Rot,
+	Nip,
+	Tuck,
+	Pick,
+	Roll,
+	Drop,
+	Peek,
+	Length,
+	Graphemes,
+	Substring,
+	Find,
+	Replace,
+	Upper,
+	Lower,
+	Title,
+	Trim,
+	TrimStart,
+	TrimEnd,
+	Repeat,
+	PadLeft,
+	PadRight,
+	Stack,
+	Stacks,
+	Delve,
+	Send,
+	Fetch,
+	Mark,
+	Rewind,
+	Enchant,
+	Bind,
+	Engrave,
+	Recall,
+	Known,
+	Forget,
+	Memories,
+	School,
+	Attune,
+	Attempt,
+	Lament,
+	Exit,
+	Verbatim,
+	Gaze,
+	Cleave,
+	Rend,
+	Mend,
+	Glance,
+	Hue,
+	Dialect,
+	Bleach,
+	Tongue,
+	Glow,
 }
 
-impl FromStr for Command {
-	type Err = MerlinError;
+// the name of each command, giving us both a parser and a list of every command (for completion)
 
-	fn from_str(cmd: &str) -> Result<Self, Self::Err> {
-		match cmd {
-			"genesis"    => Ok(Command::Genesis),
-			"spine"      => Ok(Command::Spine),
-			"carved"     => Ok(Command::Carved),
-			"incant"     => Ok(Command::Incant),
-			"decant"     => Ok(Command::Decant),
-			"infuse"     => Ok(Command::Infuse),
-			"defuse"     => Ok(Command::Defuse),
-			"shelve"     => Ok(Command::Shelve),
-			"focus"      => Ok(Command::Focus),
-			"volume"     => Ok(Command::Volume),
-			"volumes"    => Ok(Command::Volumes),
-			"spot"       => Ok(Command::Spot),
-			"span"       => Ok(Command::Span),
-			"pin"        => Ok(Command::Pin),
-			"columns"    => Ok(Command::Columns),
-			"traverse"   => Ok(Command::Traverse),
-			"shift"      => Ok(Command::Shift),
-			"appear"     => Ok(Command::Appear),
-			"infix"      => Ok(Command::Infix),
-			"peer"       => Ok(Command::Peer),
-			"inscribe"   => Ok(Command::Inscribe),
-			"trample"    => Ok(Command::Trample),
-			"burn"       => Ok(Command::Burn),
-			"shave"      => Ok(Command::Shave),
-			"molecule"   => Ok(Command::Molecule),
-			"atoms"      => Ok(Command::Atoms),
-			"pen"        => Ok(Command::Pen),
-			"orbit"      => Ok(Command::Orbit),
-			"pervert"    => Ok(Command::Pervert),
-			"decay"      => Ok(Command::Decay),
-			"destroy"    => Ok(Command::Destroy),
-			"tether"     => Ok(Command::Tether),
-			"stitch"     => Ok(Command::Stitch),
-			"fray"       => Ok(Command::Fray),
-			"atom"       => Ok(Command::Atom),
-			"scribe"     => Ok(Command::Scribe),
-			"adieu"      => Ok(Command::Adieu),
-			"nomen"      => Ok(Command::Nomen),
-			"bottle"     => Ok(Command::Bottle),
-			"disenchant" => Ok(Command::Disenchant),
-			"smash"      => Ok(Command::Smash),
-			"rune"       => Ok(Command::Rune),
-			"merlin"     => Ok(Command::Merlin),
-			"summon"     => Ok(Command::Summon),
-			"dub"        => Ok(Command::Dub),
-			"carve"      => Ok(Command::Carve),
-			"spellbook"  => Ok(Command::Spellbook),
-			"protean"    => Ok(Command::Protean),
-			_            => Err(MerlinError::UnknownCommand),
+macro_rules! name_commands {
+	($($name:literal => $command:ident,)*) => {
+		impl FromStr for Command {
+			type Err = MerlinError;
+
+			fn from_str(cmd: &str) -> Result<Self, Self::Err> {
+				match cmd {
+					$($name => Ok(Command::$command),)*
+					_       => Err(MerlinError::UnknownCommand),
+				}
+			}
 		}
+
+		pub const COMMAND_NAMES: &[&str] = &[$($name),*];
 	}
 }
 
+name_commands! {
+	"genesis"    => Genesis,
+	"spine"      => Spine,
+	"carved"     => Carved,
+	"incant"     => Incant,
+	"decant"     => Decant,
+	"infuse"     => Infuse,
+	"defuse"     => Defuse,
+	"shelve"     => Shelve,
+	"focus"      => Focus,
+	"volume"     => Volume,
+	"volumes"    => Volumes,
+	"spot"       => Spot,
+	"span"       => Span,
+	"pin"        => Pin,
+	"columns"    => Columns,
+	"traverse"   => Traverse,
+	"shift"      => Shift,
+	"appear"     => Appear,
+	"infix"      => Infix,
+	"peer"       => Peer,
+	"inscribe"   => Inscribe,
+	"trample"    => Trample,
+	"burn"       => Burn,
+	"shave"      => Shave,
+	"molecule"   => Molecule,
+	"atoms"      => Atoms,
+	"pen"        => Pen,
+	"orbit"      => Orbit,
+	"pervert"    => Pervert,
+	"decay"      => Decay,
+	"destroy"    => Destroy,
+	"tether"     => Tether,
+	"stitch"     => Stitch,
+	"fray"       => Fray,
+	"atom"       => Atom,
+	"scribe"     => Scribe,
+	"adieu"      => Adieu,
+	"nomen"      => Nomen,
+	"bottle"     => Bottle,
+	"disenchant" => Disenchant,
+	"smash"      => Smash,
+	"rune"       => Rune,
+	"merlin"     => Merlin,
+	"summon"     => Summon,
+	"dub"        => Dub,
+	"carve"      => Carve,
+	"spellbook"  => Spellbook,
+	"protean"    => Protean,
+	"wander"     => Wander,
+	"locus"      => Locus,
+	"imbue"      => Imbue,
+	"purge"      => Purge,
+	"essence"    => Essence,
+	"conjure"    => Conjure,
+	"chronicle"  => Chronicle,
+	"restrict"   => Restrict,
+	"+"          => Add,
+	"-"          => Subtract,
+	"*"          => Multiply,
+	"/"          => Divide,
+	"%"          => Modulo,
+	"min"        => Min,
+	"max"        => Max,
+	"="          => Equal,
+	"!="         => Unequal,
+	"<"          => Less,
+	">"          => Greater,
+	"<="         => LessEqual,
+	">="         => GreaterEqual,
+	"heed"       => Heed,
+	"augur"      => Augur,
+	"chant"      => Chant,
+	"ritual"     => Ritual,
+	"abyss"      => Abyss,
+	"eternity"   => Eternity,
+	"cast"       => Cast,
+	"dip"        => Dip,
+	"keep"       => Keep,
+	"transmute"  => Transmute,
+	"dup"        => Dup,
+	"over"       => Over,
+	"rot"        => Rot,
+	"nip"        => Nip,
+	"tuck"       => Tuck,
+	"pick"       => Pick,
+	"roll"       => Roll,
+	"drop"       => Drop,
+	"peek"       => Peek,
+	"length"     => Length,
+	"graphemes"  => Graphemes,
+	"substring"  => Substring,
+	"find"       => Find,
+	"replace"    => Replace,
+	"upper"      => Upper,
+	"lower"      => Lower,
+	"title"      => Title,
+	"trim"       => Trim,
+	"trim-start" => TrimStart,
+	"trim-end"   => TrimEnd,
+	"repeat"     => Repeat,
+	"pad-left"   => PadLeft,
+	"pad-right"  => PadRight,
+	"stack"      => Stack,
+	"stacks"     => Stacks,
+	"delve"      => Delve,
+	"send"       => Send,
+	"fetch"      => Fetch,
+	"mark"       => Mark,
+	"rewind"     => Rewind,
+	"enchant"    => Enchant,
+	"bind"       => Bind,
+	"engrave"    => Engrave,
+	"recall"     => Recall,
+	"known"      => Known,
+	"forget"     => Forget,
+	"memories"   => Memories,
+	"school"     => School,
+	"attune"     => Attune,
+	"attempt"    => Attempt,
+	"lament"     => Lament,
+	"exit"       => Exit,
+	"verbatim"   => Verbatim,
+	"gaze"       => Gaze,
+	"cleave"     => Cleave,
+	"rend"       => Rend,
+	"mend"       => Mend,
+	"glance"     => Glance,
+	"hue"        => Hue,
+	"dialect"    => Dialect,
+	"bleach"     => Bleach,
+	"tongue"     => Tongue,
+	"glow"       => Glow,
+}
+
 impl Command {
 	// check if the number of arguments are valid, and if so return the needed amount of arguments
 
@@ -140,11 +324,21 @@ impl Command {
 			Command::Nomen                                                                                                                                                 => all_with_min(1), // min of 1
 			Command::Spot    | Command::Span     | Command::Molecule  | Command::Pen      | Command::Orbit  | Command::Pervert  | Command::Decay   | Command::Destroy    |
 			Command::Atom    | Command::Scribe   | Command::Adieu     | Command::Carve    | Command::Pin    | Command::Columns  | Command::Burn    | Command::Volume     |
-			Command::Volumes | Command::Carved   | Command::Atoms     | Command::Tether   | Command::Stitch | Command::Fray     | Command::Protean                         => 0,
-			Command::Focus   | Command::Traverse | Command::Appear    | Command::Shave    | Command::Shelve | Command::Inscribe | Command::Trample | Command::Incant     | 
+			Command::Volumes | Command::Carved   | Command::Atoms     | Command::Tether   | Command::Stitch | Command::Fray     | Command::Protean | Command::Locus      |
+			Command::Dup     | Command::Restrict | Command::Over      | Command::Rot      | Command::Nip    | Command::Tuck     | Command::Stack   | Command::Stacks     |
+			Command::Mark    | Command::Rewind   | Command::Memories  | Command::Lament   | Command::Gaze   | Command::Cleave   | Command::Rend    | Command::Mend         => 0,
+			Command::Focus   | Command::Traverse | Command::Appear    | Command::Shave    | Command::Shelve | Command::Inscribe | Command::Trample | Command::Incant     |
 			Command::Summon  | Command::Dub      | Command::Spellbook | Command::Shift    | Command::Infix  | Command::Spine    | Command::Merlin  | Command::Disenchant |
-			Command::Smash   | Command::Decant   | Command::Rune                                                                                                           => 1,
-			Command::Infuse  | Command::Peer     | Command::Defuse                                                                                                         => 2,
+			Command::Smash   | Command::Decant   | Command::Rune      | Command::Wander   | Command::Purge  | Command::Essence  | Command::Conjure | Command::Chronicle  |
+			Command::Abyss   | Command::Eternity | Command::Cast      | Command::Pick     | Command::Roll   | Command::Drop     | Command::Peek    | Command::Length     |
+			Command::Upper   | Command::Lower    | Command::Graphemes | Command::Title    | Command::Trim   | Command::TrimEnd  | Command::Delve   | Command::TrimStart  |
+			Command::Fetch   | Command::Recall   | Command::Known     | Command::Forget   | Command::School | Command::Attune   | Command::Attempt | Command::Exit       |
+			Command::Glance  | Command::Verbatim | Command::Bleach    | Command::Tongue   | Command::Glow                                                                  => 1,
+			Command::Infuse  | Command::Peer     | Command::Defuse    | Command::Imbue    | Command::Add    | Command::Modulo   | Command::Min     | Command::Multiply   |
+			Command::Max     | Command::Subtract | Command::LessEqual | Command::Equal    | Command::Less   | Command::Unequal  | Command::Greater | Command::Divide     |
+			Command::Heed    | Command::Chant    | Command::Ritual    | Command::Dip      | Command::Keep   | Command::Find     | Command::Repeat  | Command::Transmute  |
+			Command::Send    | Command::Bind     | Command::Engrave   | Command::Dialect  | Command::GreaterEqual                                                          => 2,
+			Command::Augur   | Command::Replace  | Command::Substring | Command::PadLeft  | Command::Hue    | Command::PadRight | Command::Enchant                         => 3,
 			Command::Bottle                                                                                                                                                => choose_mm(2, 1),
 			Command::Genesis                                                                                                                                               => choose_mm(1, 0),
 		};
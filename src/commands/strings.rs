@@ -0,0 +1,105 @@
+// commands for manipulating the text of atoms
+
+extern crate unicode_segmentation;
+
+use unicode_segmentation::UnicodeSegmentation;
+use crate::error::MerlinError;
+
+// the largest atom repeat and pad will build, in bytes
+
+const MAX_ATOM_LEN: usize = 1 << 30;
+
+// return the length of an atom in unicode scalars
+
+pub fn length(atom: &str) -> String {
+	atom.chars().count().to_string()
+}
+
+// return the length of an atom in user-perceived characters
+
+pub fn graphemes(atom: &str) -> String {
+	atom.graphemes(true).count().to_string()
+}
+
+// return a piece of an atom, starting at a certain character
+
+pub fn substring(atom: &str, start: usize, len: usize) -> Result<String, MerlinError> {
+	if start < 1 || start > atom.chars().count() + 1 {
+		return Err(MerlinError::OutOfBounds);
+	}
+
+	Ok(atom.chars().skip(start - 1).take(len).collect())
+}
+
+// return the position of the first occurrence of a piece of text, 0 if it can't be found
+
+pub fn find(atom: &str, needle: &str) -> String {
+	match atom.find(needle) {
+		Some(i) => (atom[..i].chars().count() + 1).to_string(),
+		None    => String::from("0"),
+	}
+}
+
+// replace every occurrence of a piece of text
+
+pub fn replace(atom: &str, from: &str, to: &str) -> Result<String, MerlinError> {
+	if from.is_empty() {
+		return Err(MerlinError::InvalidOrNoArguments);
+	}
+
+	Ok(atom.replace(from, to))
+}
+
+// capitalize the first letter of every word
+
+pub fn title(atom: &str) -> String {
+	let mut titled = String::with_capacity(atom.len());
+	let mut start = true;
+
+	for ch in atom.chars() {
+		if start {
+			titled.extend(ch.to_uppercase());
+		} else {
+			titled.extend(ch.to_lowercase());
+		}
+
+		start = ch.is_whitespace();
+	}
+
+	titled
+}
+
+// repeat an atom a certain number of times
+
+pub fn repeat(atom: &str, times: usize) -> Result<String, MerlinError> {
+	atom.len().checked_mul(times)
+		.filter(|len| *len <= MAX_ATOM_LEN)
+		.ok_or(MerlinError::Overflow)?;
+
+	Ok(atom.repeat(times))
+}
+
+// pad an atom out to a certain width with a character
+
+pub fn pad(atom: &str, width: usize, padding: &str, left: bool) -> Result<String, MerlinError> {
+	let mut chars = padding.chars();
+
+	let ch = match (chars.next(), chars.next()) {
+		(Some(ch), None) => ch,
+		_                => return Err(MerlinError::InvalidOrNoArguments), // we need exactly one character
+	};
+
+	let count = width.saturating_sub(atom.chars().count());
+
+	count.checked_mul(ch.len_utf8())
+		.and_then(|len| len.checked_add(atom.len()))
+		.filter(|len| *len <= MAX_ATOM_LEN)
+		.ok_or(MerlinError::Overflow)?;
+
+	let fill: String = std::iter::repeat_n(ch, count).collect();
+
+	match left {
+		true  => Ok(fill + atom),
+		false => Ok(atom.to_string() + &fill),
+	}
+}
@@ -0,0 +1,548 @@
+// a small line editor for the repl: emacs style keys, history and completion
+
+use std::{env, fs::{self, OpenOptions}, io::{self, Write}, path::PathBuf};
+use nix::{poll::{self, PollFd, PollFlags}, unistd};
+use crate::terminal::{RawMode, STDIN};
+
+const HISTORY_FILE: &str = "merlin/history";
+const HISTORY_LIMIT: usize = 1000;
+
+// bracketed paste: the terminal wraps anything pasted in these, so we can tell it wasn't typed
+
+const PASTE_ON: &str = "\x1b[?2004h";
+const PASTE_OFF: &str = "\x1b[?2004l";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+// how often we check if the screen needs redrawing while waiting for a key, in milliseconds
+
+const IDLE_TIMEOUT: i32 = 250;
+
+// what the user entered
+
+pub enum Input {
+	Line(String),
+	Paste(String), // something that was pasted, and shouldn't be run as commands
+}
+
+// what the editor needs from whoever is using it
+
+pub trait Helper {
+	// draw the prompt again, after we've had to print something else
+
+	fn prompt(&mut self);
+
+	// return where the word before the cursor starts (in characters), and what it could be completed to
+
+	fn complete(&self, before: &str, after: &str) -> (usize, Vec<String>);
+
+	// scroll whatever is being shown by a number of pages
+
+	fn scroll(&mut self, _pages: isize) {}
+
+	// check if the screen needs redrawing, say after the terminal was resized
+
+	fn stale(&mut self) -> bool {
+		false
+	}
+
+	// get the screen ready for handing the terminal back while we're suspended, and take it back again
+
+	fn suspend(&mut self) {}
+
+	fn resume(&mut self) {}
+}
+
+// a single keypress
+
+#[derive(Copy, Clone)]
+enum Key {
+	Char(char),
+	Ctrl(char),
+	Alt(char),
+	Up,
+	Down,
+	Left,
+	Right,
+	Home,
+	End,
+	Delete,
+	PageUp,
+	PageDown,
+	Backspace,
+	Enter,
+	Tab,
+	Paste,
+	Unknown,
+}
+
+// the line being edited
+
+#[derive(Default)]
+struct Line {
+	chars: Vec<char>,
+	cursor: usize,
+	drawn: usize, // where the cursor was last drawn
+
+	pasted: bool, // was anything pasted into the line?
+}
+
+pub struct Editor {
+	history: Vec<String>,
+	path: Option<PathBuf>,
+	killed: String, // the last text killed, for yanking back
+}
+
+impl Editor {
+	// create an editor, loading any history we saved before
+
+	pub fn new() -> Self {
+		let path = history_path();
+
+		let mut history: Vec<String> = path.as_ref()
+			.and_then(|p| fs::read_to_string(p).ok())
+			.map(|h| h.lines().map(String::from).collect())
+			.unwrap_or_default();
+
+		history.drain(..history.len().saturating_sub(HISTORY_LIMIT));
+
+		Self { history, path, killed: String::new() }
+	}
+
+	// add a line to the history, saving it for next time (the history file has a line per entry, so
+	// anything spanning several lines would come back in pieces and is left out)
+
+	pub fn remember(&mut self, line: &str) {
+		if line.trim().is_empty() || line.contains(['\n', '\r']) || self.history.last().is_some_and(|l| l == line) {
+			return;
+		}
+
+		self.history.push(line.to_string());
+		self.history.drain(..self.history.len().saturating_sub(HISTORY_LIMIT));
+
+		if let Some(path) = &self.path {
+			if let Some(dir) = path.parent() {
+				let _ = fs::create_dir_all(dir);
+			}
+
+			if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+				let _ = writeln!(file, "{}", line);
+			}
+		}
+	}
+
+	// read a line, returning None at the end of input (Ctrl-D on an empty line)
+
+	pub fn read_line(&mut self, helper: &mut impl Helper) -> Option<Input> {
+		let raw = RawMode::enter(STDIN)?;
+
+		emit(PASTE_ON);
+
+		let input = self.edit(helper, &raw);
+
+		emit(PASTE_OFF);
+
+		input
+	}
+
+	fn edit(&mut self, helper: &mut impl Helper, raw: &RawMode) -> Option<Input> {
+		let mut line = Line::default();
+		let mut index = self.history.len(); // where we are in the history
+		let mut saved = Vec::new();         // the line we were editing before looking through the history
+
+		loop {
+			// redraw everything if we have to while we wait
+
+			if !ready() {
+				if helper.stale() {
+					helper.prompt();
+					line.drawn = 0;
+					refresh(&mut line);
+				}
+
+				continue;
+			}
+
+			let key = read_key()?;
+
+			match key {
+				Key::Enter                      => {
+					emit("\r\n");
+					return Some(line.input());
+				}
+				Key::Ctrl('d') if line.chars.is_empty() => {
+					emit("\r\n");
+					return None;
+				}
+				Key::Ctrl('c')                  => {
+					// throw the line away and start over
+
+					emit("^C\r\n");
+					helper.prompt();
+
+					line = Line::default();
+					index = self.history.len();
+				}
+				Key::Char(c)                    => {
+					line.chars.insert(line.cursor, c);
+					line.cursor += 1;
+				}
+				Key::Left | Key::Ctrl('b')      => line.cursor = line.cursor.saturating_sub(1),
+				Key::Right | Key::Ctrl('f')     => line.cursor = (line.cursor + 1).min(line.chars.len()),
+				Key::Home | Key::Ctrl('a')      => line.cursor = 0,
+				Key::End | Key::Ctrl('e')       => line.cursor = line.chars.len(),
+				Key::Alt('b')                   => line.cursor = word_start(&line.chars, line.cursor),
+				Key::Alt('f')                   => line.cursor = word_end(&line.chars, line.cursor),
+				Key::Backspace | Key::Ctrl('h') if line.cursor > 0 => {
+					line.cursor -= 1;
+					line.chars.remove(line.cursor);
+				}
+				Key::Delete | Key::Ctrl('d') if line.cursor < line.chars.len() => {
+					line.chars.remove(line.cursor);
+				}
+				k @ (Key::Ctrl('k') | Key::Ctrl('u') | Key::Ctrl('w') | Key::Alt('d')) => {
+					let (start, end) = match k {
+						Key::Ctrl('k') => (line.cursor, line.chars.len()),
+						Key::Ctrl('u') => (0, line.cursor),
+						Key::Ctrl('w') => (word_start(&line.chars, line.cursor), line.cursor),
+						_              => (line.cursor, word_end(&line.chars, line.cursor)),
+					};
+
+					self.kill(&mut line, start, end);
+				}
+				Key::Ctrl('y')                  => {
+					let killed: Vec<char> = self.killed.chars().collect();
+
+					line.chars.splice(line.cursor..line.cursor, killed.iter().copied());
+					line.cursor += killed.len();
+				}
+				Key::Up | Key::Ctrl('p') if index > 0 => {
+					if index == self.history.len() {
+						saved = line.chars.clone();
+					}
+
+					index -= 1;
+					line.chars = self.history[index].chars().collect();
+					line.cursor = line.chars.len();
+				}
+				Key::Down | Key::Ctrl('n') if index < self.history.len() => {
+					index += 1;
+
+					line.chars = match self.history.get(index) {
+						Some(h) => h.chars().collect(),
+						None    => saved.clone(),
+					};
+
+					line.cursor = line.chars.len();
+				}
+				Key::PageUp | Key::PageDown     => {
+					helper.scroll(match key {
+						Key::PageUp => -1,
+						_           => 1,
+					});
+
+					helper.prompt();
+					line.drawn = 0;
+				}
+				Key::Ctrl('z')                  => {
+					// give the terminal back while we're stopped
+
+					emit(PASTE_OFF);
+					helper.suspend();
+
+					raw.suspend();
+
+					helper.resume();
+					emit(PASTE_ON);
+
+					helper.prompt();
+					line.drawn = 0;
+				}
+				Key::Ctrl('l')                  => {
+					emit("\x1b[H\x1b[2J");
+					helper.prompt();
+					line.drawn = 0;
+				}
+				Key::Tab                        => complete(&mut line, helper),
+				Key::Paste                      => {
+					let text = read_paste()?;
+
+					line.chars.splice(line.cursor..line.cursor, text.chars());
+					line.cursor += text.chars().count();
+					line.pasted = true;
+
+					// we can't edit multiple lines, so take them as they are
+
+					if text.contains('\n') {
+						emit("\r\n");
+						return Some(line.input());
+					}
+				}
+				_                               => (),
+			}
+
+			refresh(&mut line);
+		}
+	}
+
+	// cut out part of the line, so it can be yanked back later
+
+	fn kill(&mut self, line: &mut Line, start: usize, end: usize) {
+		if start < end {
+			self.killed = line.chars.drain(start..end).collect();
+			line.cursor = start;
+		}
+	}
+}
+
+impl Line {
+	// what was entered, remembering whether any of it was pasted
+
+	fn input(&self) -> Input {
+		let text = self.chars.iter().collect();
+
+		match self.pasted {
+			true  => Input::Paste(text),
+			false => Input::Line(text),
+		}
+	}
+}
+
+// redraw the line, relative to where the cursor is (we don't know how long the prompt is)
+
+fn refresh(line: &mut Line) {
+	let mut out = String::new();
+
+	if line.drawn > 0 {
+		out.push_str(&format!("\x1b[{}D", line.drawn));
+	}
+
+	out.extend(line.chars.iter());
+	out.push_str("\x1b[K");
+
+	if line.chars.len() > line.cursor {
+		out.push_str(&format!("\x1b[{}D", line.chars.len() - line.cursor));
+	}
+
+	emit(&out);
+
+	line.drawn = line.cursor;
+}
+
+// complete the word before the cursor, listing the choices if there is more than one
+
+fn complete(line: &mut Line, helper: &mut impl Helper) {
+	let before: String = line.chars[..line.cursor].iter().collect();
+	let after: String = line.chars[line.cursor..].iter().collect();
+
+	let (start, choices) = helper.complete(&before, &after);
+
+	let replacement = match choices.len() {
+		0 => {
+			emit("\x07");
+			return;
+		}
+		1 => {
+			// finish the word, unless it is a directory we might want to keep going into
+
+			let mut word = choices[0].clone();
+
+			if !word.ends_with('/') {
+				word.push(' ');
+			}
+
+			word
+		}
+		_ => common_prefix(&choices),
+	};
+
+	if replacement.chars().count() > line.cursor - start {
+		line.chars.splice(start..line.cursor, replacement.chars());
+		line.cursor = start + replacement.chars().count();
+	} else {
+		// we can't get any further on our own, show the choices
+
+		emit(&format!("\r\n{}\r\n", choices.join("  ")));
+		helper.prompt();
+
+		line.drawn = 0;
+	}
+}
+
+// the longest prefix shared by every choice
+
+fn common_prefix(choices: &[String]) -> String {
+	let mut prefix: Vec<char> = choices[0].chars().collect();
+
+	for choice in &choices[1..] {
+		let shared = prefix.iter()
+			.zip(choice.chars())
+			.take_while(|(a, b)| *a == b)
+			.count();
+
+		prefix.truncate(shared);
+	}
+
+	prefix.into_iter().collect()
+}
+
+// where the word before (or after) a position starts (or ends)
+
+fn word_start(chars: &[char], mut i: usize) -> usize {
+	while i > 0 && chars[i - 1].is_whitespace() {
+		i -= 1;
+	}
+
+	while i > 0 && !chars[i - 1].is_whitespace() {
+		i -= 1;
+	}
+
+	i
+}
+
+fn word_end(chars: &[char], mut i: usize) -> usize {
+	while i < chars.len() && chars[i].is_whitespace() {
+		i += 1;
+	}
+
+	while i < chars.len() && !chars[i].is_whitespace() {
+		i += 1;
+	}
+
+	i
+}
+
+// read a single keypress, decoding escape sequences and utf-8
+
+fn read_key() -> Option<Key> {
+	let key = match read_byte()? {
+		b'\r' | b'\n'    => Key::Enter,
+		b'\t'            => Key::Tab,
+		0x7f             => Key::Backspace,
+		0x1b             => read_escape()?,
+		b @ 0x01..=0x1a  => Key::Ctrl((b - 1 + b'a') as char),
+		b @ 0x20..=0x7e  => Key::Char(b as char),
+		b                => {
+			// the first byte of a utf-8 sequence tells us how long it is
+
+			let len = match b {
+				0xc0..=0xdf => 2,
+				0xe0..=0xef => 3,
+				0xf0..=0xf7 => 4,
+				_           => return Some(Key::Unknown),
+			};
+
+			let mut bytes = vec![b];
+
+			for _ in 1..len {
+				bytes.push(read_byte()?);
+			}
+
+			match std::str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()) {
+				Some(c) => Key::Char(c),
+				None    => Key::Unknown,
+			}
+		}
+	};
+
+	Some(key)
+}
+
+// decode whatever follows an escape: an alt-modified key or a control sequence
+
+fn read_escape() -> Option<Key> {
+	let key = match read_byte()? {
+		b'[' => {
+			// read the parameters up to the final byte
+
+			let mut params = String::new();
+
+			let last = loop {
+				match read_byte()? {
+					b @ 0x40..=0x7e => break b,
+					b               => params.push(b as char),
+				}
+			};
+
+			match (params.as_str(), last) {
+				("", b'A')                => Key::Up,
+				("", b'B')                => Key::Down,
+				("", b'C')                => Key::Right,
+				("", b'D')                => Key::Left,
+				("", b'H') | ("1" | "7", b'~') => Key::Home,
+				("", b'F') | ("4" | "8", b'~') => Key::End,
+				("3", b'~')               => Key::Delete,
+				("200", b'~')             => Key::Paste,
+				("5", b'~')               => Key::PageUp,
+				("6", b'~')               => Key::PageDown,
+				_                         => Key::Unknown,
+			}
+		}
+		b'O' => match read_byte()? {
+			b'H' => Key::Home,
+			b'F' => Key::End,
+			_    => Key::Unknown,
+		},
+		0x7f => Key::Ctrl('w'), // alt-backspace kills a word, like ctrl-w
+		b    => Key::Alt((b as char).to_ascii_lowercase()),
+	};
+
+	Some(key)
+}
+
+// read everything up to the end of a paste, with the terminal's carriage returns turned into newlines
+
+fn read_paste() -> Option<String> {
+	let mut bytes = Vec::new();
+
+	while !bytes.ends_with(PASTE_END) {
+		bytes.push(read_byte()?);
+	}
+
+	bytes.truncate(bytes.len() - PASTE_END.len());
+
+	let text = String::from_utf8_lossy(&bytes)
+		.replace("\r\n", "\n")
+		.replace('\r', "\n");
+
+	Some(text.strip_suffix('\n').unwrap_or(&text).to_string())
+}
+
+// wait a little while for a key to be pressed
+
+fn ready() -> bool {
+	let mut fds = [PollFd::new(STDIN, PollFlags::POLLIN)];
+
+	!matches!(poll::poll(&mut fds, IDLE_TIMEOUT), Ok(0))
+}
+
+fn read_byte() -> Option<u8> {
+	let mut byte = [0; 1];
+
+	loop {
+		match unistd::read(STDIN, &mut byte) {
+			Ok(1)                  => return Some(byte[0]),
+			Err(nix::Error::EINTR) => (),
+			_                      => return None,
+		}
+	}
+}
+
+// write to the terminal right away
+
+fn emit(s: &str) {
+	let mut stdout = io::stdout();
+
+	let _ = stdout.write_all(s.as_bytes());
+	let _ = stdout.flush();
+}
+
+// history is kept in $XDG_STATE_HOME/merlin/history
+
+fn history_path() -> Option<PathBuf> {
+	let state = env::var_os("XDG_STATE_HOME")
+		.filter(|dir| !dir.is_empty())
+		.map(PathBuf::from)
+		.or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))?;
+
+	Some(state.join(HISTORY_FILE))
+}
@@ -1,6 +1,33 @@
 use std::fmt;
 use crate::util::ERROR_PREFIX;
 
+// exit statuses
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_SYNTAX: i32 = 2;
+pub const EXIT_IO: i32 = 3;
+pub const EXIT_UNSAVED: i32 = 4;
+
+// the most sites an error's trace lists, since recursion through several nomens can't be folded up
+
+const TRACE_LIMIT: usize = 16;
+
+// a place where notation is being parsed, and how many atoms into it we are
+
+#[derive(Debug, Clone)]
+pub struct Site {
+	pub name: String,
+	pub atom: usize,
+}
+
+// an error, along with the sites it happened in (outermost first)
+
+pub struct Fault<'a> {
+	pub error: &'a MerlinError,
+	pub trace: &'a [Site],
+}
+
 // our error structure
 
 #[derive(Debug)]
@@ -17,10 +44,55 @@ pub enum MerlinError {
 	FileAlreadyExists,
 	BufferNotNamed,
 	UnknownNomen,
+	InvalidDirectory,
+	UnsetEnvironment,
+	ExternalForbidden,
+	WriteForbidden,
+	NotANumber,
+	DivideByZero,
+	Overflow,
+	RecursionLimit,
+	IterationLimit,
+	UnbalancedQuotation,
+	UnterminatedString,
+	UnterminatedComment,
+	UnterminatedVerbatim,
+	InvalidEscape,
+	UnknownStack,
+	NoMark,
+	NoFrame,
+	UnknownVariable,
+	UnknownModule,
+	NotATerminal,
+	NotGazing,
+	LastPane,
+	UnknownSyntax,
+	InvalidPattern(String),
+	UnknownStyle(String),
+	UnreadableSpellbook(String),
+	MissingParameters(String, Vec<String>), // the nomen, and the parameters it expects
 }
 
-impl fmt::Display for MerlinError {
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl MerlinError {
+	// the exit status for a kind of error
+
+	pub fn status(&self) -> i32 {
+		match self {
+			MerlinError::InvalidSyntax | MerlinError::UnknownCommand | MerlinError::InvalidOrNoArguments |
+			MerlinError::NotANumber | MerlinError::UnbalancedQuotation | MerlinError::UnterminatedString |
+			MerlinError::UnterminatedComment | MerlinError::UnterminatedVerbatim | MerlinError::InvalidEscape |
+			MerlinError::MissingParameters(..) | MerlinError::InvalidPattern(..) |
+			MerlinError::UnknownStyle(..)                                                   => EXIT_SYNTAX,
+			MerlinError::CreationOrWriteFailed | MerlinError::ReadFailed | MerlinError::CannotOpen |
+			MerlinError::FileAlreadyExists | MerlinError::InvalidDirectory | MerlinError::UnknownModule |
+			MerlinError::UnreadableSpellbook(..)                                            => EXIT_IO,
+			_                                                                                => EXIT_FAILURE,
+		}
+	}
+
+	// the message describing an error
+
+	pub fn message(&self) -> String {
 		let msg = match self {
 			MerlinError::OutOfBounds           => "index out of bounds",
 			MerlinError::InvalidSyntax         => "invalid syntax",
@@ -34,8 +106,91 @@ impl fmt::Display for MerlinError {
 			MerlinError::FileAlreadyExists     => "file already exists or buffer is already named",
 			MerlinError::BufferNotNamed        => "buffer is not named",
 			MerlinError::UnknownNomen          => "unknown nomen",
+			MerlinError::InvalidDirectory      => "unable to change directory, are you sure it exists?",
+			MerlinError::UnsetEnvironment      => "environment variable is not set",
+			MerlinError::ExternalForbidden     => "external commands are disabled in restricted mode",
+			MerlinError::WriteForbidden        => "writing outside of the allowed directories is disabled in restricted mode",
+			MerlinError::NotANumber            => "atom is not a number",
+			MerlinError::DivideByZero          => "division by zero",
+			MerlinError::Overflow              => "arithmetic overflow",
+			MerlinError::RecursionLimit        => "nomen recursion limit reached",
+			MerlinError::IterationLimit        => "loop iteration limit reached",
+			MerlinError::UnbalancedQuotation   => "unbalanced quotation brackets",
+			MerlinError::UnterminatedString    => "unterminated string literal",
+			MerlinError::UnterminatedComment   => "unterminated block comment",
+			MerlinError::UnterminatedVerbatim  => "unterminated verbatim block",
+			MerlinError::InvalidEscape         => "invalid escape sequence in string literal",
+			MerlinError::UnknownStack          => "unknown stack",
+			MerlinError::NoMark                => "no stack depth has been marked",
+			MerlinError::NoFrame               => "locals can only be bound inside of a nomen",
+			MerlinError::UnknownVariable       => "unknown variable",
+			MerlinError::NotATerminal          => "visual mode needs a terminal",
+			MerlinError::NotGazing             => "panes only exist in visual mode",
+			MerlinError::LastPane              => "can't mend the last pane",
+			MerlinError::UnknownSyntax         => "unknown syntax",
+			MerlinError::UnknownModule         => "unable to find module, is it in ~/.merlin/lib or MERLIN_PATH?",
+			MerlinError::MissingParameters(nomen, params) => {
+				return format!("nomen \"{}\" expects parameters: {}", nomen, params.join(" "));
+			}
+			MerlinError::InvalidPattern(p)     => return format!("invalid pattern \"{}\"", p),
+			MerlinError::UnknownStyle(s)       => return format!("unknown style \"{}\"", s),
+			MerlinError::UnreadableSpellbook(p) => return format!("unable to read spellbook \"{}\"", p),
 		};
 
-		write!(f, "{} {}", ERROR_PREFIX, msg)
+		msg.to_string()
+	}
+}
+
+impl fmt::Display for MerlinError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} {}", ERROR_PREFIX, self.message())
+	}
+}
+
+impl fmt::Display for Site {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.name)?;
+
+		if self.atom > 0 {
+			write!(f, ", atom {}", self.atom)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl fmt::Display for Fault<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.error)?;
+
+		// innermost site first, like a backtrace, folding up the same site repeated by recursion
+
+		let mut sites = self.trace.iter().rev().peekable();
+		let mut shown = 0;
+
+		while let Some(site) = sites.next() {
+			// past the limit, skip to where it all started
+
+			if shown == TRACE_LIMIT && sites.len() > 0 {
+				write!(f, "\n\t... {} more frames", sites.len())?;
+				return write!(f, "\n\tat {}", self.trace[0]);
+			}
+
+			write!(f, "\n\tat {}", site)?;
+
+			let mut repeats = 0;
+
+			while sites.next_if(|s| s.name == site.name && s.atom == site.atom).is_some() {
+				repeats += 1;
+			}
+
+			if repeats > 0 {
+				write!(f, "\n\t... {} more frames in {}", repeats, site.name)?;
+			}
+
+			shown += 1;
+		}
+
+		Ok(())
 	}
 }
@@ -0,0 +1,301 @@
+// syntax highlighting, with rules picked by a volume's file extension, shebang or modeline
+
+use std::collections::HashMap;
+use regex::Regex;
+use crate::{error::MerlinError, volume::Volume};
+
+const RESET: &str = "\x1b[0m";
+
+// how many lines at either end of a volume are searched for a modeline
+
+const MODELINE_REACH: usize = 5;
+
+// the rules we ship with: the syntax, a pattern, and the style to draw its matches in
+
+const RULES: &[(&str, &str, &str)] = &[
+	("rust", r"//.*", "dim"),
+	("rust", r#"b?"(?:\\.|[^"\\])*"?"#, "green"),
+	("rust", r"b?'(?:\\.|[^'\\])'", "green"),
+	("rust", r"#!?\[.*\]", "magenta"),
+	("rust", r"\b(?:as|async|await|break|const|continue|crate|dyn|else|enum|extern|fn|for|if|impl|in|let|loop|match|mod|move|mut|pub|ref|return|self|Self|static|struct|super|trait|type|unsafe|use|where|while)\b", "bold blue"),
+	("rust", r"\b(?:true|false|Some|None|Ok|Err)\b|\b\d[\w.]*", "cyan"),
+	("rust", r"\b\w+!", "magenta"),
+	("rust", r"\b[A-Z]\w*", "yellow"),
+
+	("shell", r"(?:^|\s)#.*", "dim"),
+	("shell", r#""(?:\\.|[^"\\])*"?"#, "green"),
+	("shell", r"'[^']*'?", "green"),
+	("shell", r"\$(?:\{[^}]*\}?|\w+|[@*#?$!-])", "cyan"),
+	("shell", r"\b(?:if|then|else|elif|fi|for|while|until|do|done|case|esac|in|function|return|local|export|readonly)\b", "bold blue"),
+
+	("markdown", r"^#{1,6}\s.*", "bold blue"),
+	("markdown", r"^\s*(?:```|~~~).*", "dim"),
+	("markdown", r"^\s*>.*", "italic"),
+	("markdown", r"^\s*(?:[-*+]|\d+\.)\s", "yellow"),
+	("markdown", r"`[^`]*`", "green"),
+	("markdown", r"\*\*[^*]+\*\*|__[^_]+__", "bold"),
+	("markdown", r"\*[^*\s][^*]*\*|\b_[^_]+_\b", "italic"),
+	("markdown", r"!?\[[^\]]*\]\([^)]*\)", "cyan"),
+
+	("toml", r"#.*", "dim"),
+	("toml", r#""(?:\\.|[^"\\])*"?|'[^']*'?"#, "green"),
+	("toml", r"^\s*\[.*\]", "bold blue"),
+	("toml", r"^\s*[\w.-]+\s*=", "yellow"),
+	("toml", r"\b(?:true|false)\b|[+-]?\b\d[\w:.+-]*", "cyan"),
+
+	("merlin", r"#\|.*?(?:\|#|$)", "dim"),
+	("merlin", r"#.*", "dim"),
+	("merlin", r#""(?:\\.|[^"\\])*"?"#, "green"),
+	("merlin", r";\S+", "bold blue"),
+	("merlin", r"[\[\]]", "magenta"),
+	("merlin", r"-?\b\d+(?:\.\d+)?\b", "cyan"),
+];
+
+// file extensions and interpreters, and the syntax they are written in
+
+const DIALECTS: &[(&str, &str)] = &[
+	("rs", "rust"),
+	("sh", "shell"),
+	("bash", "shell"),
+	("zsh", "shell"),
+	("ksh", "shell"),
+	("dash", "shell"),
+	("md", "markdown"),
+	("mn", "merlin"),
+];
+
+// a pattern, and the SGR attributes of whatever it matches
+
+struct Rule {
+	pattern: Regex,
+	style: String,
+}
+
+// the rules of a language, earlier rules winning when two match at the same place
+
+#[derive(Default)]
+pub struct Syntax {
+	rules: Vec<Rule>,
+}
+
+impl Syntax {
+	// the style of each character of a line
+
+	pub fn styles(&self, line: &str) -> Vec<Option<&str>> {
+		let mut bytes = vec![None; line.len()];
+		let mut pos = 0;
+
+		// take whichever rule matches soonest, and carry on after it
+
+		while let Some((m, rule)) = self.rules.iter()
+			.filter_map(|r| r.pattern.find_at(line, pos)
+				.filter(|m| !m.is_empty())
+				.map(|m| (m, r)))
+			.min_by_key(|(m, _)| m.start())
+		{
+			bytes[m.range()].fill(Some(rule.style.as_str()));
+			pos = m.end();
+		}
+
+		line.char_indices()
+			.map(|(i, _)| bytes[i])
+			.collect()
+	}
+
+	// a line with the escape sequences to draw it in color
+
+	pub fn paint(&self, line: &str) -> String {
+		let mut out = String::new();
+		let mut current = None;
+
+		for (c, style) in line.chars().zip(self.styles(line)) {
+			out.push_str(&restyle(&mut current, style));
+			out.push(c);
+		}
+
+		out.push_str(&restyle(&mut current, None));
+
+		out
+	}
+}
+
+// every syntax we know of, and how to tell which one a volume is written in
+
+pub struct Highlighter {
+	syntaxes: HashMap<String, Syntax>,
+	dialects: HashMap<String, String>, // extensions, interpreters and modeline names, and the syntax they stand for
+	modeline: Regex,
+	shebang: Regex,
+}
+
+impl Default for Highlighter {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Highlighter {
+	// a highlighter with the built in syntaxes
+
+	pub fn new() -> Self {
+		let mut highlighter = Self {
+			syntaxes: HashMap::new(),
+			dialects: DIALECTS.iter()
+				.map(|(d, s)| (d.to_string(), s.to_string()))
+				.collect(),
+			modeline: Regex::new(r"\b(?:vim?|ex|merlin):.*\b(?:ft|filetype|syntax)=([\w+.-]+)").unwrap(),
+			shebang: Regex::new(r"^#!\s*(\S+)(?:\s+(\S+))?").unwrap(),
+		};
+
+		// added in reverse, since new rules go in front of the old ones
+
+		for (syntax, pattern, style) in RULES.iter().rev() {
+			highlighter.hue(pattern, style, syntax).unwrap();
+		}
+
+		highlighter
+	}
+
+	// add a rule to a syntax (creating it if need be), taking priority over the rules already there
+
+	pub fn hue(&mut self, pattern: &str, style: &str, syntax: &str) -> Result<(), MerlinError> {
+		let rule = Rule {
+			pattern: Regex::new(pattern).or(Err(MerlinError::InvalidPattern(pattern.to_string())))?,
+			style: sgr(style)?,
+		};
+
+		let rules = &mut self.syntaxes.entry(syntax.to_string())
+			.or_default()
+			.rules;
+
+		rules.retain(|r| r.pattern.as_str() != pattern); // a rule for the same pattern is replaced
+		rules.insert(0, rule);
+
+		Ok(())
+	}
+
+	// say that an extension, interpreter or modeline name means a certain syntax
+
+	pub fn dialect(&mut self, name: &str, syntax: &str) {
+		self.dialects.insert(name.to_string(), syntax.to_string());
+	}
+
+	// forget all of the rules of a syntax, so it can be defined again from scratch
+
+	pub fn bleach(&mut self, syntax: &str) -> Result<(), MerlinError> {
+		self.syntaxes.get_mut(syntax)
+			.ok_or(MerlinError::UnknownSyntax)?
+			.rules
+			.clear();
+
+		Ok(())
+	}
+
+	// the syntax a name stands for, checking that we know of it (a blank name means none at all)
+
+	pub fn tongue(&self, name: &str) -> Result<Option<String>, MerlinError> {
+		if name.is_empty() {
+			return Ok(None);
+		}
+
+		self.resolve(name)
+			.map(|s| Some(s.to_string()))
+			.ok_or(MerlinError::UnknownSyntax)
+	}
+
+	// the syntax a volume is written in: whatever it was set to, or from a modeline, shebang or file extension
+
+	pub fn syntax(&self, vol: &Volume) -> Option<&Syntax> {
+		let name = match &vol.syntax {
+			Some(s) => Some(s.as_str()),
+			None    => self.modeline(vol)
+				.or_else(|| self.shebang(vol))
+				.or_else(|| self.resolve(vol.name()?.extension()?.to_str()?)),
+		};
+
+		self.syntaxes.get(name?)
+	}
+
+	// the name of a syntax, or one of its dialects
+
+	fn resolve(&self, name: &str) -> Option<&str> {
+		let name = match self.syntaxes.contains_key(name) {
+			true  => name,
+			false => self.dialects.get(name)?,
+		};
+
+		self.syntaxes.get_key_value(name)
+			.map(|(k, _)| k.as_str())
+	}
+
+	// look for something like "vim: ft=rust" near the start or end of a volume
+
+	fn modeline(&self, vol: &Volume) -> Option<&str> {
+		let span = vol.span();
+
+		(0..span.min(MODELINE_REACH))
+			.chain(span.saturating_sub(MODELINE_REACH).max(MODELINE_REACH)..span)
+			.filter_map(|i| vol.line_text(i))
+			.find_map(|line| self.resolve(self.modeline.captures(&line)?.get(1)?.as_str()))
+	}
+
+	// look at the interpreter named on the first line, looking past env
+
+	fn shebang(&self, vol: &Volume) -> Option<&str> {
+		let line = vol.line_text(0)?;
+		let captures = self.shebang.captures(&line)?;
+
+		let interpreter = match captures[1].rsplit('/').next()? {
+			"env" => captures.get(2)?.as_str(),
+			i     => i,
+		};
+
+		self.resolve(interpreter)
+	}
+}
+
+// turn a style like "bold red" into the parameters of an SGR escape sequence
+
+fn sgr(style: &str) -> Result<String, MerlinError> {
+	let codes = style.split_whitespace()
+		.map(|s| match s {
+			"bold"      => Ok("1"),
+			"dim"       => Ok("2"),
+			"italic"    => Ok("3"),
+			"underline" => Ok("4"),
+			"black"     => Ok("30"),
+			"red"       => Ok("31"),
+			"green"     => Ok("32"),
+			"yellow"    => Ok("33"),
+			"blue"      => Ok("34"),
+			"magenta"   => Ok("35"),
+			"cyan"      => Ok("36"),
+			"white"     => Ok("37"),
+			_           => Err(MerlinError::UnknownStyle(s.to_string())),
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(codes.join(";"))
+}
+
+// the escape sequence to switch from one style to another, if they differ
+
+pub fn restyle<'a>(current: &mut Option<&'a str>, style: Option<&'a str>) -> String {
+	if *current == style {
+		return String::new();
+	}
+
+	let mut out = String::new();
+
+	if current.is_some() {
+		out.push_str(RESET);
+	}
+
+	if let Some(s) = style {
+		out.push_str(&format!("\x1b[{}m", s));
+	}
+
+	*current = style;
+
+	out
+}
@@ -4,16 +4,40 @@
 
 extern crate clap;
 extern crate ctrlc;
+extern crate shellexpand;
 
-use clap::{Arg, App, crate_version};
+use std::{fs, process, thread};
+use error::{EXIT_IO, EXIT_FAILURE};
+use clap::{Arg, ArgGroup, App, crate_version};
 
 mod plane;
 mod volume;
 mod commands;
 mod error;
 mod util;
+mod terminal;
+mod editor;
+mod highlight;
+
+// nomens are expanded recursively, so we run on a thread with a deeper stack than the main one
+
+const STACK_SIZE: usize = 64 * 1024 * 1024;
 
 fn main() {
+	let merlin = thread::Builder::new()
+		.stack_size(STACK_SIZE)
+		.spawn(run)
+		.unwrap_or_else(|_| {
+			eprintln!("{} unable to start", util::ERROR_PREFIX);
+			process::exit(EXIT_FAILURE);
+		});
+
+	// a panic has already been reported by the time we get here
+
+	process::exit(merlin.join().unwrap_or(EXIT_FAILURE));
+}
+
+fn run() -> i32 {
 	let merlin_args = App::new("merlin:")
 		.about("an esoteric text editor for wizards")
 		.version(crate_version!())
@@ -25,6 +49,61 @@ fn main() {
 			.short("s")
 			.long("stdin")
 			.help("Parse stdin as merlin notation"))
+		.arg(Arg::with_name("restricted")
+			.short("r")
+			.long("restricted")
+			.help("Disable external commands"))
+		.arg(Arg::with_name("allow")
+			.long("allow")
+			.value_name("DIR")
+			.multiple(true)
+			.number_of_values(1)
+			.requires("restricted")
+			.help("Only allow writing files inside of DIR when restricted"))
+		.arg(Arg::with_name("strict")
+			.long("strict")
+			.help("Stop at the first error"))
+		.arg(Arg::with_name("init")
+			.long("init")
+			.value_name("FILE")
+			.conflicts_with("no-init")
+			.help("Read FILE as the startup spellbook"))
+		.arg(Arg::with_name("no-init")
+			.long("no-init")
+			.help("Don't read a startup spellbook"))
+		.arg(Arg::with_name("open")
+			.short("o")
+			.long("open")
+			.value_name("FILE")
+			.multiple(true)
+			.number_of_values(1)
+			.help("Summon FILE into its own volume (repeat for more files), -o +N before a FILE jumps to line N"))
+		.arg(Arg::with_name("batch")
+			.short("b")
+			.long("batch")
+			.value_name("FILE")
+			.multiple(true)
+			.requires("program")
+			.conflicts_with_all(&["NOTATION", "stdin"])
+			.help("Run notation against each FILE, writing back the ones it changes"))
+		.arg(Arg::with_name("eval")
+			.short("e")
+			.long("eval")
+			.value_name("NOTATION")
+			.requires("batch")
+			.help("Notation to run against each batch file"))
+		.arg(Arg::with_name("script")
+			.short("f")
+			.long("file")
+			.value_name("SCRIPT")
+			.requires("batch")
+			.help("Spellbook to run against each batch file"))
+		.group(ArgGroup::with_name("program")
+			.args(&["eval", "script"]))
+		.arg(Arg::with_name("dry-run")
+			.long("dry-run")
+			.requires("batch")
+			.help("Print a diff of each batch file instead of writing it"))
 		.arg(Arg::with_name("NOTATION")
 			.index(1)
 			.multiple(true)
@@ -32,11 +111,62 @@ fn main() {
 		.get_matches();
 
 	let mut p = plane::Plane::new();
+
+	if merlin_args.is_present("strict") {
+		p.strict();
+	}
+
+	// lock things down before any notation is parsed
+
+	if merlin_args.is_present("restricted") {
+		p.restrict();
+
+		if let Some(dirs) = merlin_args.values_of("allow") {
+			dirs.for_each(|d| p.allow(d));
+		}
+	}
 	
+	// read the startup spellbook, from $XDG_CONFIG_HOME/merlin/init.mn or ~/.merlin/spellbook.mn by default.
+	// the fallback is the shipped spellbook, whose banner would get mixed up with the output of scripts and pipes
+
+	if !merlin_args.is_present("no-init") {
+		let interactive = !merlin_args.is_present("stdin") && !merlin_args.is_present("batch") &&
+			terminal::is_terminal(terminal::STDIN) && terminal::is_terminal(terminal::STDOUT);
+
+		p.init(merlin_args.value_of("init"), interactive);
+	}
+
+	// edit each of the batch files, and then we're done
+
+	if let Some(paths) = merlin_args.values_of("batch") {
+		let script = match merlin_args.value_of("script") {
+			Some(s) => match fs::read_to_string(&*shellexpand::tilde(s)) {
+				Ok(script) => script,
+				Err(_)     => {
+					eprintln!("{} unable to read {}", util::ERROR_PREFIX, s);
+					return EXIT_IO;
+				}
+			},
+			None    => merlin_args.value_of("eval").unwrap_or_default().to_string(),
+		};
+
+		p.batch(&paths.collect::<Vec<&str>>(), &script, merlin_args.is_present("dry-run"));
+
+		return p.status();
+	}
+
+	// open any files we were given
+
+	if let Some(files) = merlin_args.values_of("open") {
+		p.open(&files.collect::<Vec<&str>>());
+	}
+
 	// parse the first argument(s) as MN
 
 	if let Some(n) = merlin_args.values_of("NOTATION") {
-		p.parse_line(&n.collect::<Vec<&str>>().join(" "));
+		if p.is_running() {
+			p.parse_line(&n.collect::<Vec<&str>>().join(" "));
+		}
 	}
 
 	// only handle interrupts when the -i flag is NOT present
@@ -45,11 +175,15 @@ fn main() {
 		util::err_msg(ctrlc::set_handler(|| ()), "can't handle Ctrl-C events");
 	}
 
-	// check to see if we should parse stdin...
+	// check to see if we should parse stdin, unless we have already been told to stop...
 
-	if merlin_args.is_present("stdin") {
-		p.parse_stdin()
-	} else { // otherwise start the REPL
-		p.repl();
+	if p.is_running() {
+		if merlin_args.is_present("stdin") {
+			p.parse_stdin()
+		} else { // otherwise start the REPL
+			p.repl();
+		}
 	}
+
+	p.status()
 }
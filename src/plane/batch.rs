@@ -0,0 +1,137 @@
+// running the same notation against many files, like sed -i
+
+extern crate similar;
+
+use std::{collections::HashMap, fs, mem, path::{Path, PathBuf}};
+use gapbuf::GapBuffer;
+use similar::TextDiff;
+use crate::{error::MerlinError, util::ERROR_PREFIX, volume::Volume};
+use super::{Plane, Vision, stack::Stack, stacks::PRIME_STACK, lexer::Lexer};
+
+// everything a script leaves behind that mustn't carry over to the next file.
+// nomens and variables are shared, so a script can keep count across files
+
+#[derive(Default)]
+struct Scratch {
+	volumes: GapBuffer<Volume>,
+	current_volume: usize,
+
+	stack: Stack,
+	stack_name: String,
+	stacks: HashMap<String, Stack>,
+	marks: Vec<(String, usize)>,
+
+	vision: Vision,
+	verbatim: Option<(String, Vec<String>)>,
+	lexer: Lexer,
+}
+
+impl Scratch {
+	fn new() -> Self {
+		Self {
+			stack_name: String::from(PRIME_STACK),
+			..Self::default()
+		}
+	}
+}
+
+impl Plane {
+	// run a script against each file, writing back (or diffing) the ones it changes
+
+	pub fn batch(&mut self, paths: &[&str], script: &str, dry_run: bool) {
+		self.dry_run = dry_run; // the script's own ;carve mustn't write anything either
+
+		for path in paths {
+			if !self.running {
+				break;
+			}
+
+			// each file starts from a clean slate, and whatever it leaves behind is thrown away after
+
+			let mut scratch = Scratch::new();
+
+			self.swap_scratch(&mut scratch);
+			self.enter(path.to_string());
+
+			if let Err(e) = self.batch_file(path, script) {
+				self.report(&e);
+			}
+
+			self.leave();
+			self.swap_scratch(&mut scratch);
+		}
+	}
+
+	fn swap_scratch(&mut self, scratch: &mut Scratch) {
+		mem::swap(&mut self.volumes, &mut scratch.volumes);
+		mem::swap(&mut self.current_volume, &mut scratch.current_volume);
+
+		mem::swap(&mut self.stack, &mut scratch.stack);
+		mem::swap(&mut self.stack_name, &mut scratch.stack_name);
+		mem::swap(&mut self.stacks, &mut scratch.stacks);
+		mem::swap(&mut self.marks, &mut scratch.marks);
+
+		mem::swap(&mut self.vision, &mut scratch.vision);
+		mem::swap(&mut self.verbatim, &mut scratch.verbatim);
+		mem::swap(&mut self.lexer, &mut scratch.lexer);
+	}
+
+	fn batch_file(&mut self, path: &str, script: &str) -> Result<(), MerlinError> {
+		// summoning a file that doesn't exist would create it, which isn't what editing one means
+
+		if !Path::new(&*shellexpand::tilde(path)).is_file() {
+			return Err(MerlinError::CannotOpen);
+		}
+
+		self.summon(path)?;
+
+		// the script may open other volumes, so keep track of ours by name
+
+		let name: Option<PathBuf> = self.volumes[0].name().map(|n| n.to_path_buf());
+		let before = self.volumes[0].text();
+		let faults = self.faults;
+
+		self.enter(String::new());
+
+		for (n, line) in script.lines().enumerate() {
+			if !self.running {
+				break;
+			}
+
+			self.relabel(format!("script line {}", n + 1));
+			self.parse_line(line);
+		}
+
+		self.leave();
+
+		// the script may have closed the volume
+
+		let index = self.volumes.iter()
+			.position(|v| v.name() == name.as_deref())
+			.ok_or(MerlinError::NoVolumes)?;
+
+		// don't write out half finished edits
+
+		if self.faults > faults {
+			eprintln!("{} {}: left unchanged because of errors", ERROR_PREFIX, path);
+			return Ok(());
+		}
+
+		if self.volumes[index].text() == before {
+			return Ok(());
+		}
+
+		self.current_volume = index;
+
+		if self.dry_run {
+			let old = fs::read_to_string(&*shellexpand::tilde(path)).unwrap_or_default();
+			let new = self.volumes[index].text() + "\n";
+
+			print!("{}", TextDiff::from_lines(&old, &new).unified_diff().header(path, path));
+
+			Ok(())
+		} else {
+			self.carve()
+		}
+	}
+}
@@ -0,0 +1,89 @@
+// helping out the line editor by completing commands, nomens and paths
+
+extern crate shellexpand;
+
+use std::{fs, path::Path};
+use crate::commands::COMMAND_NAMES;
+use super::{Plane, Vision};
+
+const COMMAND_PREFIX: char = ';';
+
+// commands whose argument is a path
+
+const PATH_COMMANDS: &[&str] = &[";summon", ";spellbook", ";dub", ";attune", ";wander"];
+
+impl Plane {
+	// the word being completed starts at a character index, with these choices for it
+
+	pub fn completions(&self, before: &str, after: &str) -> (usize, Vec<String>) {
+		let start = before.char_indices()
+			.rev()
+			.find(|(_, c)| c.is_whitespace())
+			.map_or(0, |(i, c)| i + c.len_utf8()); // whitespace isn't always a single byte
+		let word = &before[start..];
+		let start = before[..start].chars().count();
+
+		// scribe mode is just text
+
+		if let Vision::Scribe = self.vision {
+			return (start, Vec::new());
+		}
+
+		let mut choices = if let Some(name) = word.strip_prefix(COMMAND_PREFIX) {
+			COMMAND_NAMES.iter()
+				.copied()
+				.chain(self.nomens.keys().map(String::as_str))
+				.filter(|c| c.starts_with(name))
+				.map(|c| format!("{}{}", COMMAND_PREFIX, c))
+				.collect()
+		} else if word.contains('/') || word.starts_with(['~', '.']) ||
+			after.split_whitespace().next().is_some_and(|next| PATH_COMMANDS.contains(&next)) {
+			paths(word)
+		} else {
+			Vec::new()
+		};
+
+		choices.sort_unstable();
+		choices.dedup();
+
+		(start, choices)
+	}
+}
+
+// list the paths starting with a partial path
+
+fn paths(word: &str) -> Vec<String> {
+	let (dir, prefix) = match word.rfind('/') {
+		Some(i) => word.split_at(i + 1),
+		None    => ("", word),
+	};
+
+	let listing = match dir {
+		"" => fs::read_dir("."),
+		d  => fs::read_dir(Path::new(&*shellexpand::tilde(d))),
+	};
+
+	let entries = match listing {
+		Ok(e)  => e,
+		Err(_) => return Vec::new(),
+	};
+
+	entries.filter_map(Result::ok)
+		.filter_map(|entry| {
+			let name = entry.file_name().into_string().ok()?;
+
+			// hidden files only show up when asked for
+
+			if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+				return None;
+			}
+
+			let slash = match entry.path().is_dir() {
+				true  => "/",
+				false => "",
+			};
+
+			Some(format!("{}{}{}", dir, name, slash))
+		})
+		.collect()
+}
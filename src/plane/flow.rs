@@ -0,0 +1,118 @@
+// control flow: conditionals and loops
+
+use crate::{commands::arithmetic::truthy, error::MerlinError};
+use super::Plane;
+
+impl Plane {
+	// run some code if a condition is true, or some other code if it isn't
+
+	pub fn heed(&mut self, condition: &str, then: &str, otherwise: Option<&str>) -> Result<(), MerlinError> {
+		if truthy(condition) {
+			self.cast(then)
+		} else if let Some(o) = otherwise {
+			self.cast(o)
+		} else {
+			Ok(())
+		}
+	}
+
+	// run some code a certain number of times
+
+	pub fn chant(&mut self, times: usize, code: &str) -> Result<(), MerlinError> {
+		if times > self.max_iterations {
+			return Err(MerlinError::IterationLimit);
+		}
+
+		for _ in 0..times {
+			if !self.running {
+				break;
+			}
+
+			self.cast(code)?;
+		}
+
+		Ok(())
+	}
+
+	// run some code for as long as the condition code leaves a true atom on the stack
+
+	pub fn ritual(&mut self, condition: &str, code: &str) -> Result<(), MerlinError> {
+		for _ in 0..self.max_iterations {
+			if !self.running || !self.divine(condition)? {
+				return Ok(());
+			}
+
+			self.cast(code)?;
+		}
+
+		// the loop may have finished on its last allowed iteration, or been told to stop
+
+		if !self.running {
+			return Ok(());
+		}
+
+		match self.divine(condition)? {
+			true  => Err(MerlinError::IterationLimit),
+			false => Ok(()),
+		}
+	}
+
+	// run the condition of a loop
+
+	fn divine(&mut self, condition: &str) -> Result<bool, MerlinError> {
+		self.cast(condition)?;
+
+		Ok(truthy(&self.stack.pop()?))
+	}
+
+	// run some code with an atom tucked out of the way, putting it back afterwards
+
+	pub fn dip(&mut self, atom: String, code: &str) -> Result<(), MerlinError> {
+		self.cast(code)?;
+		self.stack.push(atom);
+
+		Ok(())
+	}
+
+	// run some code on an atom, keeping a copy of it afterwards
+
+	pub fn keep(&mut self, atom: String, code: &str) -> Result<(), MerlinError> {
+		self.stack.push(atom.clone());
+		self.cast(code)?;
+		self.stack.push(atom);
+
+		Ok(())
+	}
+
+	// run some code on each line of an atom, collecting what it leaves behind
+
+	pub fn transmute(&mut self, text: &str, code: &str) -> Result<String, MerlinError> {
+		let mut lines = Vec::new();
+
+		for line in text.lines() {
+			if !self.running {
+				break;
+			}
+
+			self.stack.push(line.to_string());
+			self.cast(code)?;
+
+			lines.push(self.stack.pop()?);
+		}
+
+		Ok(lines.join("\n"))
+	}
+
+	// run some code, returning the message of any error instead of printing it, or a blank atom
+
+	pub fn attempt(&mut self, code: &str) -> String {
+		match self.cast(code) {
+			Ok(()) => String::new(),
+			Err(e) => {
+				self.failure = None; // the error was caught, forget where it happened
+
+				e.message()
+			}
+		}
+	}
+}
@@ -0,0 +1,53 @@
+// the spellbook read when merlin starts up
+
+extern crate shellexpand;
+
+use std::{env, path::PathBuf};
+use crate::error::MerlinError;
+use super::Plane;
+
+const INIT_FILE: &str = "merlin/init.mn";
+const FALLBACK_INIT: &str = "~/.merlin/spellbook.mn";
+
+impl Plane {
+	// read the startup spellbook, either the one given or the first one we can find (skipping the fallback if asked to)
+
+	pub fn init(&mut self, file: Option<&str>, fallback: bool) {
+		let path = match file {
+			Some(f) => PathBuf::from(&*shellexpand::tilde(f)),
+			None    => match find_init(fallback) {
+				Some(p) => p,
+				None    => return, // no startup spellbook, that's fine
+			}
+		};
+
+		if let Err(e) = self.read_spellbook(&path) {
+			// the trace can't say which file it was if we never got to read it
+
+			let e = match e {
+				MerlinError::CannotOpen | MerlinError::ReadFailed => MerlinError::UnreadableSpellbook(path.display().to_string()),
+				e                                                 => e,
+			};
+
+			self.report(&e);
+		}
+	}
+}
+
+// look for $XDG_CONFIG_HOME/merlin/init.mn, and then ~/.merlin/spellbook.mn
+
+fn find_init(fallback: bool) -> Option<PathBuf> {
+	let config = env::var_os("XDG_CONFIG_HOME")
+		.filter(|dir| !dir.is_empty())
+		.map(PathBuf::from)
+		.unwrap_or_else(|| PathBuf::from(&*shellexpand::tilde("~/.config")));
+
+	let mut paths = vec![config.join(INIT_FILE)];
+
+	if fallback {
+		paths.push(PathBuf::from(&*shellexpand::tilde(FALLBACK_INIT)));
+	}
+
+	paths.into_iter()
+		.find(|path| path.is_file())
+}
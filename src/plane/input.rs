@@ -1,4 +1,4 @@
-use crate::util;
+use crate::{util, terminal, editor::{Editor, Input}};
 use super::{Plane, Vision};
 use std::io::{self, Write, BufRead};
 
@@ -7,16 +7,36 @@ impl Plane {
 		let mut input = String::new();
 		let stdin = io::stdin();
 
-		while self.running {
-			// run our prompt nomen
+		self.interactive = true; // mistakes made here don't count towards the exit status
+
+		// only use the line editor when we're talking to a person
 
-			match self.vision {
-				Vision::Atom   => self.parse_line(";atom-prompt"),
-				Vision::Scribe => self.parse_line(";scribe-prompt"),
-			};
+		let mut editor = match terminal::is_terminal(terminal::STDIN) {
+			true  => Some(Editor::new()),
+			false => None,
+		};
 
+		while self.running {
+			self.prompt(); // run our prompt nomen
 			flush_stdout();                                                          // flush stdout, handling any errors
-			util::err_msg(stdin.read_line(&mut input), "unable to read line"); // read a line of input (handle any errors)
+
+			match &mut editor {
+				Some(e) => match e.read_line(self) {
+					Some(Input::Line(line))  => {
+						if let Vision::Atom = self.vision {
+							e.remember(&line);
+						}
+
+						input = line;
+					}
+					Some(Input::Paste(text)) => {
+						self.parse_paste(&text);
+						continue;
+					}
+					None                     => break, // end of input
+				},
+				None    => util::err_msg(stdin.read_line(&mut input), "unable to read line"), // read a line of input (handle any errors)
+			}
 
 			// parse our line, stripping newlines
 
@@ -27,9 +47,12 @@ impl Plane {
 	}
 
 	pub fn parse_stdin(&mut self) {
-		for line in io::stdin().lock().lines() {
+		self.enter(String::new());
+
+		for (n, line) in io::stdin().lock().lines().enumerate() {
+			self.relabel(format!("stdin:{}", n + 1));
 			self.parse_line(strip_nl(&line
-				.expect(&format!("{} can't read stdin", util::ERROR_PREFIX))));
+				.unwrap_or_else(|_| panic!("{} can't read stdin", util::ERROR_PREFIX))));
 		
 			flush_stdout();
 
@@ -37,6 +60,8 @@ impl Plane {
 				break;
 			}
 		}
+
+		self.leave();
 	}
 }
 
@@ -45,7 +70,7 @@ impl Plane {
 fn strip_nl(input: &str) -> &str {
 	input.strip_suffix("\r\n")
 		.or_else(|| input.strip_suffix("\n"))
-		.unwrap_or(&input)
+		.unwrap_or(input)
 }
 
 // flush stdout, handling errors
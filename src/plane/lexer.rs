@@ -0,0 +1,237 @@
+// split notation up into atoms
+
+use std::{iter::Peekable, str::CharIndices};
+use crate::error::MerlinError;
+
+pub const QUOTE_OPEN: &str = "[";
+pub const QUOTE_CLOSE: &str = "]";
+
+const STRING_DELIMITER: char = '"';
+const ESCAPE: char = '\\';
+
+// comments run to the end of the line, or between the block delimiters
+
+const COMMENT: char = '#';
+const BLOCK_COMMENT_OPEN: &str = "#|";
+const BLOCK_COMMENT_CLOSE: &str = "|#";
+
+// a single word of notation: how it was written, and the atom it stands for
+
+struct Word<'a> {
+	raw: &'a str,
+	atom: String,
+}
+
+// a lexer, remembering any quotation left unfinished at the end of a line
+
+#[derive(Default)]
+pub struct Lexer {
+	quotation: Vec<String>, // the atoms of the quotation we are in the middle of
+	nesting: usize,
+
+	commented: bool,        // are we inside of a block comment?
+}
+
+impl Lexer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	// are we in the middle of a quotation?
+
+	pub fn is_pending(&self) -> bool {
+		self.nesting > 0
+	}
+
+	// are we in the middle of a block comment?
+
+	pub fn is_commented(&self) -> bool {
+		self.commented
+	}
+
+	// split a line into atoms, bundling quotations up into single atoms
+
+	pub fn feed(&mut self, line: &str) -> Vec<Result<String, MerlinError>> {
+		let mut atoms = Vec::new();
+
+		for word in scan(line, &mut self.commented) {
+			let word = match word {
+				Ok(w)  => w,
+				Err(e) => {
+					atoms.push(Err(e));
+					continue;
+				}
+			};
+
+			match word.raw {
+				QUOTE_OPEN                       => self.nesting += 1,
+				QUOTE_CLOSE if self.nesting == 0 => {
+					atoms.push(Err(MerlinError::UnbalancedQuotation));
+					continue;
+				}
+				QUOTE_CLOSE                      => self.nesting -= 1,
+				_                                => (),
+			}
+
+			if self.quotation.is_empty() && self.nesting == 0 {
+				atoms.push(Ok(word.atom));
+			} else {
+				self.quotation.push(word.raw.to_string()); // keep the original form, it will be split again when the quotation is cast
+
+				// the outermost quotation is closed, it becomes a single atom
+
+				if self.nesting == 0 {
+					atoms.push(Ok(self.quotation.join(" ")));
+					self.quotation.clear();
+				}
+			}
+		}
+
+		atoms
+	}
+
+	// split up a complete piece of notation, failing if anything is left unfinished
+
+	pub fn split(text: &str) -> Result<Vec<String>, MerlinError> {
+		let mut lexer = Self::new();
+		let atoms = lexer.feed(text).into_iter().collect();
+
+		if lexer.is_pending() {
+			return Err(MerlinError::UnbalancedQuotation);
+		}
+
+		atoms
+	}
+}
+
+// break a line up into words, separated by whitespace and skipping comments
+
+fn scan<'a>(line: &'a str, commented: &mut bool) -> Vec<Result<Word<'a>, MerlinError>> {
+	let mut words = Vec::new();
+	let mut chars = line.char_indices().peekable();
+
+	while let Some(&(start, c)) = chars.peek() {
+		if *commented {
+			// skip ahead to the end of the block comment, if it ends on this line
+
+			match line[start..].find(BLOCK_COMMENT_CLOSE) {
+				Some(i) => {
+					let end = start + i + BLOCK_COMMENT_CLOSE.len();
+
+					while chars.next_if(|&(j, _)| j < end).is_some() {}
+
+					*commented = false;
+				}
+				None    => break,
+			}
+		} else if c.is_whitespace() {
+			chars.next();
+		} else if c == COMMENT {
+			if !line[start..].starts_with(BLOCK_COMMENT_OPEN) {
+				break; // a line comment, ignore the rest of the line
+			}
+
+			chars.next();
+			chars.next();
+
+			*commented = true;
+		} else if c == STRING_DELIMITER {
+			words.push(literal(line, &mut chars));
+		} else {
+			let mut end = line.len();
+
+			while let Some(&(i, c)) = chars.peek() {
+				if c.is_whitespace() {
+					end = i;
+					break;
+				}
+
+				chars.next();
+			}
+
+			words.push(Ok(Word { raw: &line[start..end], atom: line[start..end].to_string() }));
+		}
+	}
+
+	words
+}
+
+// read a string literal, which may contain whitespace and escape sequences
+
+fn literal<'a>(line: &'a str, chars: &mut Peekable<CharIndices<'_>>) -> Result<Word<'a>, MerlinError> {
+	let (start, _) = chars.next().unwrap(); // the opening delimiter
+
+	let mut value = String::new();
+	let mut error = None;
+
+	while let Some((i, c)) = chars.next() {
+		match c {
+			STRING_DELIMITER => return match error {
+				// a leading '\' keeps the atom from being parsed as a command
+
+				None    => Ok(Word { raw: &line[start..=i], atom: format!("{}{}", ESCAPE, value) }),
+				Some(e) => Err(e),
+			},
+			ESCAPE           => if let Err(e) = escape(chars, &mut value) {
+				error = Some(e);
+			},
+			_                => value.push(c),
+		}
+	}
+
+	Err(MerlinError::UnterminatedString)
+}
+
+// decode an escape sequence inside of a string literal, keeping ones we don't know as they are (handy for regular expressions)
+
+fn escape(chars: &mut Peekable<CharIndices<'_>>, value: &mut String) -> Result<(), MerlinError> {
+	let ch = match chars.next().map(|(_, c)| c) {
+		Some('n')              => '\n',
+		Some('t')              => '\t',
+		Some('r')              => '\r',
+		Some('0')              => '\0',
+		Some(ESCAPE)           => ESCAPE,
+		Some(STRING_DELIMITER) => STRING_DELIMITER,
+
+		// unicode escapes look like \u{1F9D9}
+
+		Some('u') if chars.next_if(|&(_, c)| c == '{').is_some() => {
+			let mut hex = String::new();
+
+			loop {
+				match chars.next() {
+					Some((_, '}')) => break,
+					Some((_, c))   => hex.push(c),
+					None           => return Err(MerlinError::InvalidEscape),
+				}
+			}
+
+			u32::from_str_radix(&hex, 16).ok()
+				.and_then(char::from_u32)
+				.ok_or(MerlinError::InvalidEscape)?
+		}
+		Some(c)                => {
+			value.push(ESCAPE);
+			c
+		}
+		None                   => return Err(MerlinError::InvalidEscape),
+	};
+
+	value.push(ch);
+
+	Ok(())
+}
+
+// return the body of a quotation, if an atom is one
+
+pub fn quotation_body(atom: &str) -> Option<&str> {
+	let body = atom.strip_prefix(QUOTE_OPEN)?.strip_suffix(QUOTE_CLOSE)?;
+
+	// the brackets have to stand on their own
+
+	if body.starts_with(' ') && body.ends_with(' ') {
+		Some(body)
+	} else {
+		None
+	}
+}
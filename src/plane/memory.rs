@@ -0,0 +1,47 @@
+// variables: single atoms stored by name, never parsed
+
+use crate::{commands::arithmetic::boolean, error::MerlinError};
+use super::Plane;
+
+impl Plane {
+	// set a variable
+
+	pub fn engrave(&mut self, value: String, name: String) {
+		self.variables.insert(name, value);
+	}
+
+	// return the value of a variable
+
+	pub fn recall(&self, name: &str) -> Result<String, MerlinError> {
+		self.variables.get(name)
+			.cloned()
+			.ok_or(MerlinError::UnknownVariable)
+	}
+
+	// check if a variable is set
+
+	pub fn known(&self, name: &str) -> String {
+		boolean(self.variables.contains_key(name)).to_string()
+	}
+
+	// remove a variable
+
+	pub fn forget(&mut self, name: &str) -> Result<(), MerlinError> {
+		self.variables.remove(name)
+			.ok_or(MerlinError::UnknownVariable)?;
+
+		Ok(())
+	}
+
+	// return a list of all set variables
+
+	pub fn memories(&self) -> String {
+		let mut memory = String::new();
+
+		self.variables
+			.keys()
+			.for_each(|name| { memory.push_str(name) ; memory.push('\n')});
+
+		memory
+	}
+}
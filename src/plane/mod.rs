@@ -1,14 +1,40 @@
-use std::collections::HashMap;
+use std::{collections::{HashMap, HashSet}, path::PathBuf};
 
 use gapbuf::GapBuffer;
 
-use crate::volume::Volume;
+use crate::{volume::Volume, highlight::Highlighter};
 use stack::Stack;
+use lexer::Lexer;
+use crate::error::Site;
+use nomen::{Nomen, Frame};
 
 mod plane_commands;
 mod parse;
 mod input;
 mod stack;
+mod wards;
+mod flow;
+mod lexer;
+mod stacks;
+mod nomen;
+mod memory;
+mod schools;
+mod init;
+mod trace;
+mod status;
+mod batch;
+mod complete;
+mod visual;
+mod syntax;
+
+// default limits, keeping runaway nomens from hanging or overflowing the stack
+
+const DEFAULT_MAX_DEPTH: usize = 256;
+const DEFAULT_MAX_ITERATIONS: usize = 1_000_000;
+
+// nomens are expanded recursively, this is as deep as the stack we run on can go
+
+const MAX_DEPTH: usize = 2048;
 
 // an enum that represents the various modes of merlin
 
@@ -23,16 +49,50 @@ enum Vision {
 
 #[derive(Default)]
 pub struct Plane {
-	stack: Stack,
+	stack: Stack,                   // the stack we are currently using
+	stack_name: String,
+	stacks: HashMap<String, Stack>, // every other stack
+	marks: Vec<(String, usize)>,    // remembered stacks, and how deep they were
 
 	volumes: GapBuffer<Volume>,
 	current_volume: usize,
 
 	vision: Vision, // current vision
+	verbatim: Option<(String, Vec<String>)>, // the terminator and lines of a verbatim block we are reading
+	visual: Option<visual::Visual>,          // the full screen view, when we're in it
+	highlighter: Highlighter,
 
 	running: bool,
+	strict: bool,             // stop at the first uncaught error?
+	interactive: bool,        // are we at the repl, where errors have already been seen?
 
-	nomens: HashMap<String, Vec<String>>,
+	exit_status: Option<i32>, // set by ;exit
+	error_status: Option<i32>, // set by the first uncaught error
+	faults: usize,            // how many uncaught errors there have been
+
+	nomens: HashMap<String, Nomen>,
+	frames: Vec<Frame>,       // locals of the nomens we are expanding
+
+	school: Option<String>,   // the namespace nomens are being defined in
+	attuned: HashSet<PathBuf>, // modules that have already been read
+
+	trace: Vec<Site>,         // what we are in the middle of parsing, for error messages
+	failure: Option<Vec<Site>>, // where the error we are unwinding from happened
+
+	lament: String,           // the message of the last uncaught error
+	lamenting: bool,          // are we running the error nomen?
+
+	variables: HashMap<String, String>,
+
+	restricted: bool,         // are external commands and file writes locked down?
+	sanctums: Vec<PathBuf>,   // directories we may still write to while restricted
+	dry_run: bool,            // are batch files only being diffed, with nothing written?
+
+	depth: usize,             // how deeply nested the nomen we are expanding is
+	max_depth: usize,
+	max_iterations: usize,    // how many times a single loop may run
+
+	lexer: Lexer,             // holds on to quotations spanning multiple lines
 }
 
 impl Plane {
@@ -41,33 +101,72 @@ impl Plane {
 	pub fn new() -> Self {
 		Self {
 			stack: Stack::new(),
+			stack_name: String::from(stacks::PRIME_STACK),
+			stacks: HashMap::new(),
+			marks: Vec::new(),
+
 			volumes: GapBuffer::new(),
 
 			current_volume: 0,
 
 			vision: Vision::Atom,
+			verbatim: None,
+			visual: None,
+			highlighter: Highlighter::new(),
 
 			running: true,
+			strict: false,
+			interactive: false,
+
+			exit_status: None,
+			error_status: None,
+			faults: 0,
 
 			// built in nomens
 
-			nomens: HashMap::<String, Vec<String>>::from([
-				(String::from("new"), vec![String::from("\n")]),
-				(String::from("tab"), vec![String::from("\t")]),
-				(String::from("space"), vec![String::from(" ")]),
-				(String::from("blank"), vec![String::from("")]),
-				(String::from("escape"), vec![String::from("\x1B")]),
+			nomens: HashMap::from([
+				(String::from("new"), Nomen::new(vec![String::from("\n")])),
+				(String::from("tab"), Nomen::new(vec![String::from("\t")])),
+				(String::from("space"), Nomen::new(vec![String::from(" ")])),
+				(String::from("blank"), Nomen::new(vec![String::from("")])),
+				(String::from("escape"), Nomen::new(vec![String::from("\x1B")])),
 
 				// notation to be executing after each line of input is entered 
 
-				(String::from("scribe-nomen"), Vec::new()),
-				(String::from("atom-nomen"), Vec::new()),
+				(String::from("scribe-nomen"), Nomen::new(Vec::new())),
+				(String::from("atom-nomen"), Nomen::new(Vec::new())),
+
+				// notation to be executed after an error that wasn't caught
+
+				(String::from("error-nomen"), Nomen::new(Vec::new())),
 
 				// our prompts
 
-				(String::from("atom-prompt"), vec![", ".to_string(), ";pen".to_string(), ";decay".to_string()]),
-				(String::from("scribe-prompt"), Vec::new()),
+				(String::from("atom-prompt"), Nomen::new(vec![", ".to_string(), ";pen".to_string(), ";decay".to_string()])),
+				(String::from("scribe-prompt"), Nomen::new(Vec::new())),
 			]),
+			frames: Vec::new(),
+
+			school: None,
+			attuned: HashSet::new(),
+
+			trace: Vec::new(),
+			failure: None,
+
+			lament: String::new(),
+			lamenting: false,
+
+			variables: HashMap::new(),
+
+			restricted: false,
+			sanctums: Vec::new(),
+			dry_run: false,
+
+			depth: 0,
+			max_depth: DEFAULT_MAX_DEPTH,
+			max_iterations: DEFAULT_MAX_ITERATIONS,
+
+			lexer: Lexer::new(),
 		}
 	}
 
@@ -78,6 +177,7 @@ impl Plane {
 			self.volumes.push_back(v)
 		} else {
 			self.volumes.insert(self.current_volume+1, v);
+			self.reindex_panes(self.current_volume+1, true);
 		}
 	
 		// only increment when there are more than one volumes open 
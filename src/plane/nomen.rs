@@ -0,0 +1,67 @@
+// nomens with parameters, and the local atoms they can see
+
+use std::collections::HashMap;
+use crate::error::MerlinError;
+use super::{Plane, lexer::{self, Lexer}};
+
+// a nomen: the parameters it binds from the stack, and the atoms it expands to
+
+#[derive(Clone, Default)]
+pub struct Nomen {
+	pub params: Vec<String>,
+	pub atoms: Vec<String>,
+}
+
+impl Nomen {
+	pub fn new(atoms: Vec<String>) -> Self {
+		Self { params: Vec::new(), atoms }
+	}
+}
+
+// the locals of a single nomen expansion
+
+pub type Frame = HashMap<String, String>;
+
+impl Plane {
+	// create a nomen with named parameters
+
+	pub fn enchant(&mut self, params: &str, body: &str, name: String) -> Result<(), MerlinError> {
+		let params = Lexer::split(lexer::quotation_body(params).ok_or(MerlinError::InvalidSyntax)?)?;
+		let atoms = Lexer::split(lexer::quotation_body(body).ok_or(MerlinError::InvalidSyntax)?)?;
+
+		let name = self.qualify(name);
+
+		self.nomens.insert(name, Nomen { params, atoms });
+
+		Ok(())
+	}
+
+	// bind a local atom, only visible until the current nomen finishes expanding
+
+	pub fn bind(&mut self, value: String, name: String) -> Result<(), MerlinError> {
+		self.frames.last_mut()
+			.ok_or(MerlinError::NoFrame)?
+			.insert(name, value);
+
+		Ok(())
+	}
+
+	// look up a local atom of the nomen we are expanding
+
+	pub fn local(&self, name: &str) -> Option<String> {
+		self.frames.last()?.get(name).cloned()
+	}
+
+	// pop a nomen's arguments off of the stack, naming them after its parameters
+
+	pub fn frame(&mut self, name: &str, params: &[String]) -> Result<Frame, MerlinError> {
+		if self.stack.len() < params.len() {
+			return Err(MerlinError::MissingParameters(name.to_string(), params.to_vec()));
+		}
+
+		Ok(params.iter()
+			.cloned()
+			.zip(self.stack.grab(params.len()))
+			.collect())
+	}
+}
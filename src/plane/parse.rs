@@ -1,6 +1,6 @@
-use super::{Plane, Vision};
+use super::{Plane, Vision, Nomen, MAX_DEPTH, lexer::{self, Lexer}, visual::Split};
 use std::str::FromStr;
-use crate::commands::{commands, Command};
+use crate::commands::{commands, conjure, arithmetic, strings, Command};
 use crate::error::MerlinError;
 
 const COMMAND_PREFIX: &str = ";";
@@ -9,63 +9,209 @@ impl Plane {
 	// parse a line based on what mode the user is in
 
 	pub fn parse_line(&mut self, line: &str) {
+		// collect the lines of a verbatim block as they are
+
+		if let Some((terminator, lines)) = &mut self.verbatim {
+			if line != terminator {
+				lines.push(line.to_string());
+				return;
+			}
+
+			let text = lines.join("\n");
+
+			self.verbatim = None;
+			self.insert_literal(text);
+
+			return;
+		}
+
 		match self.vision {
 			Vision::Atom    => self.parse_line_atom(line),
 			Vision::Scribe  => self.parse_line_scribe(line),
 		}
 	}
 
+	// parse something that was pasted: in scribe mode it's inserted as is, commands and all
+
+	pub fn parse_paste(&mut self, text: &str) {
+		match self.vision {
+			Vision::Atom   => text.lines().for_each(|line| self.parse_line(line)),
+			Vision::Scribe => self.insert_literal(text.to_string()),
+		}
+	}
+
+	// push text without parsing it, running the scribe nomen if we're in scribe mode
+
+	fn insert_literal(&mut self, text: String) {
+		self.stack.push(text);
+
+		if let Vision::Scribe = self.vision {
+			self.atom_push(";scribe-nomen");
+		}
+	}
+
 	// scribe mode
 
 	fn parse_line_scribe(&mut self, line: &str) {
 		if self.atom_push(line) {
-			self.parse_atom(";scribe-nomen"); // we run this nomen after every line entered in scribe mode (excluding command calls)
+			self.atom_push(";scribe-nomen"); // we run this nomen after every line entered in scribe mode (excluding command calls)
 		}
 	}
 
 	// atom mode
 
 	fn parse_line_atom(&mut self, line: &str) {
-		for atom in line.split_whitespace() {
-			self.atom_push(atom);
-		}
-		
-		if line != ";atom-prompt" { // don't run if we are parsing the prompt
-			self.parse_atom(";atom-nomen"); // nomen to run after each line of input is parsed
+		for atom in self.lexer.feed(line) {
+			if !self.running {
+				break; // don't keep going after ;adieu, or an error in strict mode
+			}
+
+			self.step();
+
+			match atom {
+				Ok(a)  => { self.atom_push(&a); },
+				Err(e) => self.report(&e),
+			}
 		}
+
+		self.atom_push(";atom-nomen"); // nomen to run after each line of input is parsed
+	}
+
+	// run the prompt nomen for the current mode
+
+	pub fn prompt(&mut self) {
+		match self.vision {
+			Vision::Atom   => self.atom_push(";atom-prompt"),
+			Vision::Scribe => self.atom_push(";scribe-prompt"),
+		};
 	}
 
 	// parse and atom and push it to the stack
 
-	fn atom_push(&mut self, atom: &str) -> bool {
+	pub fn atom_push(&mut self, atom: &str) -> bool {
 		// return true if we are pushing to the stack
 
 		match self.parse_atom(atom) {
-			Some(a) => {
+			Ok(Some(a)) => {
 				self.stack.push(a);
 
 				true
 			}
-			None    => false
+			Ok(None)    => false,
+			Err(e)      => {
+				self.report(&e);
+
+				false
+			}
+		}
+	}
+
+	// expand a nomen, pushing each of its atoms
+
+	pub fn invoke(&mut self, name: &str) -> Result<(), MerlinError> {
+		let nomen = self.nomens.get(name)
+			.ok_or(MerlinError::UnknownNomen)?
+			.clone();
+
+		let label = format!("nomen {}", name);
+
+		// every expansion gets its own locals, so a nomen never sees those of whoever called it
+
+		let frame = self.frame(name, &nomen.params)?;
+
+		self.frames.push(frame);
+
+		let result = self.descend(label, &nomen.atoms);
+
+		self.frames.pop();
+
+		result
+	}
+
+	// run a piece of code: either a quotation or the name of a nomen
+
+	pub fn cast(&mut self, code: &str) -> Result<(), MerlinError> {
+		match lexer::quotation_body(code) {
+			Some(body) => self.descend(String::from("quotation"), &Lexer::split(body)?),
+			None       => self.invoke(code),
+		}
+	}
+
+	// expand a list of atoms one level deeper
+
+	fn descend(&mut self, label: String, atoms: &[String]) -> Result<(), MerlinError> {
+		self.deepen()?;
+		self.enter(label);
+
+		let mut result = Ok(());
+
+		for atom in atoms {
+			if !self.running {
+				break; // stop expanding after ;exit or ;adieu
+			}
+
+			self.step();
+
+			result = self.expand(atom);
+
+			if result.is_err() {
+				break;
+			}
 		}
+
+		self.leave();
+		self.depth -= 1;
+
+		result
+	}
+
+	// go one level deeper into nomens, merlin blocks or spellbooks, unless we are already as deep as we may go
+
+	pub fn deepen(&mut self) -> Result<(), MerlinError> {
+		if self.depth >= self.max_depth {
+			return Err(MerlinError::RecursionLimit);
+		}
+
+		self.depth += 1;
+
+		Ok(())
+	}
+
+	// parse an atom from inside of a nomen, any error stops the expansion
+
+	fn expand(&mut self, atom: &str) -> Result<(), MerlinError> {
+		match self.parse_atom(atom) {
+			Ok(Some(a)) => self.stack.push(a),
+			Ok(None)    => (),
+			Err(e)      => {
+				self.fail();
+
+				return Err(e);
+			}
+		}
+
+		Ok(())
 	}
 
 	// parse a single element, an "atom"
 
-	fn parse_atom(&mut self, atom: &str) -> Option<String> {
+	fn parse_atom(&mut self, atom: &str) -> Result<Option<String>, MerlinError> {
 		if let Some(stripped) = atom.strip_prefix(COMMAND_PREFIX) { // the atom is a command
-			match self.parse_command(stripped) {
-				Ok((command, data)) => if let Err(e) = self.run_and_handle(command, data) { eprintln!("{}", e) }, // run and handle the command
-				Err(e)              => { // the command isn't valid...
-					match self.nomens.remove_entry(stripped) { // check if it is a nomen
-						Some((k, v)) => { // add it if it is
-							for atom in &v {
-								self.atom_push(&atom);
-							}
+			// locals take precedence over nomens, but never over built in commands
 
-							self.nomens.insert(k, v);
-						},
-						None    => eprintln!("{}", e),
+			if Command::from_str(stripped).is_err() {
+				if let Some(value) = self.local(stripped) {
+					return Ok(Some(value));
+				}
+			}
+
+			match self.parse_command(stripped) {
+				Ok((command, data)) => self.run_and_handle(command, data)?, // run and handle the command
+				Err(e)              => { // the command isn't valid, check if it is a nomen
+					if self.nomens.contains_key(stripped) {
+						self.invoke(stripped)?;
+					} else {
+						return Err(e);
 					}
 				}
 			}
@@ -80,10 +226,10 @@ impl Plane {
 				out = stripped.to_string();
 			}
 
-			return Some(out);
+			return Ok(Some(out));
 		}
 
-		None
+		Ok(None)
 	}
 
 	// run and handle!!
@@ -109,17 +255,37 @@ impl Plane {
 
 	fn run_command(&mut self, command: Command, mut data: Vec<String>) -> Result<Option<String>, MerlinError> {
 		match command { // check what command is being used
-			Command::Genesis                           => if data.len() > 0 { self.genesis(&data[0]); } else { self.genesis(""); },
+			Command::Genesis                           => if !data.is_empty() { self.genesis(&data[0]); } else { self.genesis(""); },
 			Command::Spine                             => return ok_some(self.spine(parse_pos::<usize>(&data[0])?)?),
-			Command::Incant                            => return ok_some(commands::incant(&data[0])?),
-			Command::Decant                            => return ok_some(commands::decant(&data[0])?),
-			Command::Infuse                            => return ok_some(commands::infuse(&data[0], &data[1])?),
-			Command::Defuse                            => return ok_some(commands::defuse(&data[0], &data[1])?),
+			Command::Incant | Command::Decant | Command::Infuse | Command::Defuse |
+			Command::Conjure | Command::Chronicle      => {
+					// external commands can't be trusted in restricted mode
+
+					self.ward_external()?;
+
+					match command {
+						Command::Incant    => return ok_some(commands::incant(&data[0])?),
+						Command::Decant    => return ok_some(commands::decant(&data[0])?),
+						Command::Infuse    => return ok_some(commands::infuse(&data[0], &data[1])?),
+						Command::Defuse    => return ok_some(commands::defuse(&data[0], &data[1])?),
+						Command::Conjure   => { conjure::conjure(&data[0], false)?; },
+						_                  => self.genesis(&conjure::conjure(&data[0], true)?),
+					}
+				}
 			Command::Molecule                          => self.stack.molecule(),
 			Command::Pen                               => self.stack.pen(),
 			Command::Orbit                             => self.stack.orbit()?,
 			Command::Pervert			   => self.stack.pervert(),
 			Command::Decay                             => self.stack.decay(),
+			Command::Dup                               => self.stack.dup()?,
+			Command::Over                              => self.stack.over()?,
+			Command::Rot                               => self.stack.rot()?,
+			Command::Nip                               => self.stack.nip()?,
+			Command::Tuck                              => self.stack.tuck()?,
+			Command::Pick                              => self.stack.pick(parse_pos::<usize>(&data[0])?)?,
+			Command::Roll                              => self.stack.roll(parse_pos::<usize>(&data[0])?)?,
+			Command::Drop                              => self.stack.drop(parse_pos::<usize>(&data[0])?)?,
+			Command::Peek                              => self.stack.peek(parse_pos::<usize>(&data[0])?)?,
 			Command::Destroy                           => self.stack.destroy(),
 			Command::Tether                            => self.stack.tether()?,
 			Command::Fray                              => self.stack.fray()?,
@@ -127,38 +293,97 @@ impl Plane {
 			Command::Atom                              => self.vision = Vision::Atom,
 			Command::Scribe                            => self.vision = Vision::Scribe,
 			Command::Adieu                             => self.running = false,
+			Command::Gaze                              => self.gaze()?,
+			Command::Cleave                            => self.cleave(Split::Stacked)?,
+			Command::Rend                              => self.cleave(Split::Beside)?,
+			Command::Mend                              => self.mend()?,
+			Command::Glance                            => self.glance(parse_pos::<isize>(&data[0])?)?,
+			Command::Hue                               => self.highlighter.hue(&data[0], &data[1], &data[2])?,
+			Command::Dialect                           => self.highlighter.dialect(&data[0], &data[1]),
+			Command::Bleach                            => self.highlighter.bleach(&data[0])?,
+			Command::Glow                              => return ok_some(self.glow(&data[0])),
+			Command::Verbatim                          => self.verbatim = Some((data.remove(0), Vec::new())),
+			Command::Exit                              => self.exit(parse_pos::<u8>(&data[0])?),
 			Command::Nomen | Command::Bottle                            => {
 					// create a new nomen, popping the name from the data vector
 
-					let n = data.pop().unwrap();
-					self.nomens.insert(n, data);
+					let n = self.qualify(data.pop().unwrap());
+					self.nomens.insert(n, Nomen::new(data));
 				}
+			Command::Enchant                           => self.enchant(&data[0], &data[1], data[2].clone())?,
+			Command::Bind                              => self.bind(data.remove(0), data.remove(0))?,
+			Command::Engrave                           => self.engrave(data.remove(0), data.remove(0)),
+			Command::Recall                            => return ok_some(self.recall(&data[0])?),
+			Command::Known                             => return ok_some(self.known(&data[0])),
+			Command::Forget                            => self.forget(&data[0])?,
+			Command::Memories                          => return ok_some(self.memories()),
+			Command::School                            => self.school(&data[0]),
+			Command::Attune                            => self.attune(&data[0])?,
 			Command::Disenchant                        => self.disenchant(&data[0])?,
 			Command::Smash                             => self.smash(&data[0])?,
 			Command::Rune                              => return ok_some(self.rune(&data[0])?),
-			Command::Merlin                            => {
-					// make sure we are always *starting* in atom mode, but preserving the original mode
-					// for when we finish parsing 
-
-					let old_mode = self.vision;
-					self.vision = Vision::Atom;
-
-					// parse each line of the input, respecting the current mode
-
-					for line in data[0].lines() {
-						self.parse_line(line);
-					}
-
-					self.vision = old_mode; // reset mode
-				}
+			Command::Merlin                            => self.merlin(&data[0])?,
 			Command::Summon                            => self.summon(&data[0])?,
 			Command::Spellbook                         => self.spellbook(&data[0])?,
 			Command::Volume                            => return ok_some(self.volume().to_string()),
 			Command::Volumes                           => return ok_some(self.volumes.len().to_string()),
 			Command::Atoms                             => return ok_some(self.stack.len().to_string()),
 			Command::Protean                           => return ok_some(self.protean()),
+			Command::Wander                            => self.wander(&data[0])?,
+			Command::Locus                             => return ok_some(self.locus()?),
+			Command::Imbue                             => self.imbue(&data[0], &data[1])?,
+			Command::Purge                             => self.purge(&data[0])?,
+			Command::Essence                           => return ok_some(self.essence(&data[0])?),
+			Command::Restrict                          => self.restrict(),
+			Command::Add                               => return ok_some(arithmetic::add(&data[0], &data[1])?),
+			Command::Subtract                          => return ok_some(arithmetic::subtract(&data[0], &data[1])?),
+			Command::Multiply                          => return ok_some(arithmetic::multiply(&data[0], &data[1])?),
+			Command::Divide                            => return ok_some(arithmetic::divide(&data[0], &data[1])?),
+			Command::Modulo                            => return ok_some(arithmetic::modulo(&data[0], &data[1])?),
+			Command::Min                               => return ok_some(arithmetic::min(&data[0], &data[1])?),
+			Command::Max                               => return ok_some(arithmetic::max(&data[0], &data[1])?),
+			Command::Equal                             => return ok_some(arithmetic::equal(&data[0], &data[1])),
+			Command::Unequal                           => return ok_some(arithmetic::unequal(&data[0], &data[1])),
+			Command::Less                              => return ok_some(arithmetic::less(&data[0], &data[1])?),
+			Command::Greater                           => return ok_some(arithmetic::greater(&data[0], &data[1])?),
+			Command::LessEqual                         => return ok_some(arithmetic::less_equal(&data[0], &data[1])?),
+			Command::GreaterEqual                      => return ok_some(arithmetic::greater_equal(&data[0], &data[1])?),
+			Command::Length                            => return ok_some(strings::length(&data[0])),
+			Command::Graphemes                         => return ok_some(strings::graphemes(&data[0])),
+			Command::Substring                         => return ok_some(strings::substring(&data[0], parse_pos::<usize>(&data[1])?,
+											parse_pos::<usize>(&data[2])?)?),
+			Command::Find                              => return ok_some(strings::find(&data[0], &data[1])),
+			Command::Replace                           => return ok_some(strings::replace(&data[0], &data[1], &data[2])?),
+			Command::Upper                             => return ok_some(data[0].to_uppercase()),
+			Command::Lower                             => return ok_some(data[0].to_lowercase()),
+			Command::Title                             => return ok_some(strings::title(&data[0])),
+			Command::Trim                              => return ok_some(data[0].trim().to_string()),
+			Command::TrimStart                         => return ok_some(data[0].trim_start().to_string()),
+			Command::TrimEnd                           => return ok_some(data[0].trim_end().to_string()),
+			Command::Repeat                            => return ok_some(strings::repeat(&data[0], parse_pos::<usize>(&data[1])?)?),
+			Command::PadLeft                           => return ok_some(strings::pad(&data[0], parse_pos::<usize>(&data[1])?, &data[2], true)?),
+			Command::PadRight                          => return ok_some(strings::pad(&data[0], parse_pos::<usize>(&data[1])?, &data[2], false)?),
+			Command::Stack                             => return ok_some(self.stack_name.clone()),
+			Command::Stacks                            => return ok_some(self.stacks()),
+			Command::Delve                             => self.delve(&data[0]),
+			Command::Send                              => self.send(data.remove(0), &data[0]),
+			Command::Fetch                             => return ok_some(self.fetch(&data[0])?),
+			Command::Mark                              => self.mark(),
+			Command::Rewind                            => self.rewind()?,
+			Command::Cast                              => self.cast(&data[0])?,
+			Command::Attempt                           => return ok_some(self.attempt(&data[0])),
+			Command::Lament                            => return ok_some(self.lament.clone()),
+			Command::Dip                               => self.dip(data.remove(0), &data[0])?,
+			Command::Keep                              => self.keep(data.remove(0), &data[0])?,
+			Command::Transmute                         => return ok_some(self.transmute(&data[0], &data[1])?),
+			Command::Heed                              => self.heed(&data[0], &data[1], None)?,
+			Command::Augur                             => self.heed(&data[0], &data[1], Some(&data[2]))?,
+			Command::Chant                             => self.chant(parse_pos::<usize>(&data[0])?, &data[1])?,
+			Command::Ritual                            => self.ritual(&data[0], &data[1])?,
+			Command::Abyss                             => self.max_depth = parse_pos::<usize>(&data[0])?.min(MAX_DEPTH),
+			Command::Eternity                          => self.max_iterations = parse_pos::<usize>(&data[0])?,
 			_                                          => { // the following commands require buffers to be open
-				if self.volumes.len() > 0 { // buffers / files are open
+				if !self.volumes.is_empty() { // buffers / files are open
 					let cvol = &mut self.volumes[self.current_volume]; // current volume
 
 					match command {
@@ -175,7 +400,8 @@ impl Plane {
 						Command::Peer     => return ok_some(cvol.peer(parse_pos::<usize>(&data[0])?,
 											parse_pos::<usize>(&data[1])?)?),
 						Command::Dub      => cvol.dub(&data[0])?,
-						Command::Carve    => cvol.carve()?,
+						Command::Tongue   => cvol.syntax = self.highlighter.tongue(&data[0])?,
+						Command::Carve    => self.carve()?,
 						Command::Carved   => return ok_some(cvol.carved()),
 						_ => { // we are modifying the buffer...
 							cvol.written = false;
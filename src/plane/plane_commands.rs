@@ -2,9 +2,9 @@
 
 extern crate shellexpand;
 
-use std::{fs::File, io::{BufRead, BufReader}};
+use std::{env, fs::File, io::{BufRead, BufReader}, mem, path::Path};
 use crate::{volume::Volume, error::MerlinError};
-use super::Plane;
+use super::{Plane, Vision, Nomen, lexer::Lexer};
 
 impl Plane {
 	// return the name of a volume
@@ -25,7 +25,49 @@ impl Plane {
 	// open a new file
 
 	pub fn summon(&mut self, path: &str) -> Result<(), MerlinError> {
-		Ok(self.push_volume(Volume::from_file(&shellexpand::tilde(path))?))
+		self.push_volume(Volume::from_file(&shellexpand::tilde(path))?);
+
+		Ok(())
+	}
+
+	// open files from the command line, where "+N" jumps to line N (or the last line) of the next file
+
+	pub fn open(&mut self, args: &[&str]) {
+		let mut first = None;
+		let mut line = None;
+
+		for arg in args {
+			if let Some(n) = arg.strip_prefix('+') {
+				match n {
+					"" => line = Some(usize::MAX),
+					n  => match n.parse::<usize>() {
+						Ok(n)  => line = Some(n),
+						Err(_) => self.report(&MerlinError::InvalidSyntax),
+					}
+				}
+
+				continue;
+			}
+
+			match self.summon(arg) {
+				Ok(()) => {
+					let cvol = &mut self.volumes[self.current_volume];
+
+					if let Some(n) = line.take() {
+						cvol.appear(n.min(cvol.span()));
+					}
+
+					first.get_or_insert(self.current_volume);
+				}
+				Err(e) => self.report(&e),
+			}
+		}
+
+		// start out looking at the first file, like other editors
+
+		if let Some(i) = first {
+			self.current_volume = i;
+		}
 	}
 
 	// close a file / buffer
@@ -33,6 +75,7 @@ impl Plane {
 	pub fn shelve(&mut self, index: usize) -> Result<(), MerlinError> {
 		if index <= self.volumes.len() && index > 0 {
 			self.volumes.remove(index - 1);
+			self.reindex_panes(index - 1, false);
 			
 			// if we still have volumes open decrease the index by one
 
@@ -58,14 +101,29 @@ impl Plane {
 		Ok(())
 	}
 
+	// write out the current volume, unless we are restricted from doing so or only showing what would change
+
+	pub fn carve(&mut self) -> Result<(), MerlinError> {
+		if self.dry_run {
+			return Ok(());
+		}
+
+		if let Some(name) = self.volumes[self.current_volume].name() {
+			self.ward_write(name)?;
+		}
+
+		self.volumes[self.current_volume].carve()
+	}
+
 	// "clear" a nomen
 
 	pub fn disenchant(&mut self, name: &str) -> Result<(), MerlinError> {
 		// empty the vector of atoms for a certain nomen, reutrn an error if we can't find it
 
-		Ok(self.nomens.get_mut(name)
-			.ok_or(MerlinError::UnknownNomen)?
-			.clear())
+		*self.nomens.get_mut(name)
+			.ok_or(MerlinError::UnknownNomen)? = Nomen::default();
+
+		Ok(())
 	}
 
 	// remove a nomen
@@ -82,19 +140,117 @@ impl Plane {
 	// read a file an parse its contents 
 
 	pub fn spellbook(&mut self, file_path: &str) -> Result<(), MerlinError> {
-		match File::open(&*shellexpand::tilde(file_path)) {
-			Ok(file) => {
-				let reader = BufReader::new(file);
+		self.read_spellbook(Path::new(&*shellexpand::tilde(file_path)))
+	}
+
+	// parse a file line by line, keeping any namespace it declares to itself
+
+	pub fn read_spellbook(&mut self, path: &Path) -> Result<(), MerlinError> {
+		let file = File::open(path)
+			.or(Err(MerlinError::CannotOpen))?;
+
+		// a spellbook can read itself, so it counts as going a level deeper
+
+		self.deepen()?;
+
+		let school = self.school.take();
+		let outer = self.seclude();
+		let mut result = Ok(());
+
+		self.enter(path.display().to_string());
+
+		for (n, line) in BufReader::new(file).lines().enumerate() {
+			if !self.running {
+				break;
+			}
 
-				for line in reader.lines() {
-					self.parse_line(&line.or(Err(MerlinError::ReadFailed))?);
+			self.relabel(format!("{}:{}", path.display(), n + 1)); // remember where we are, for error messages
+
+			match line {
+				Ok(l)  => self.parse_line(&l),
+				Err(_) => {
+					result = Err(MerlinError::ReadFailed);
+					break;
 				}
+			}
+		}
+
+		let unfinished = self.emerge(outer);
+
+		self.leave();
+		self.school = school;
+		self.depth -= 1;
+
+		result.and(unfinished)
+	}
+
+	// parse a block of notation, a level deeper since it may well run itself
+
+	pub fn merlin(&mut self, notation: &str) -> Result<(), MerlinError> {
+		self.deepen()?;
+
+		// make sure we are always *starting* in atom mode, but preserving the original mode
+		// for when we finish parsing 
+
+		let old_mode = self.vision;
+		self.vision = Vision::Atom;
+
+		let outer = self.seclude();
 
-				Ok(())
-			},
-			Err(_)   => Err(MerlinError::CannotOpen),
+		// parse each line of the input, respecting the current mode
 
+		self.enter(String::new());
+
+		for (n, line) in notation.lines().enumerate() {
+			if !self.running {
+				break;
+			}
+
+			self.relabel(format!("merlin block, line {}", n + 1));
+			self.parse_line(line);
+		}
+
+		let unfinished = self.emerge(outer);
+
+		self.leave();
+
+		self.vision = old_mode; // reset mode
+		self.depth -= 1;
+
+		unfinished
+	}
+
+	// set aside the lexer and any verbatim block being read, so a spellbook or merlin block starts from scratch
+
+	fn seclude(&mut self) -> (Lexer, Option<(String, Vec<String>)>) {
+		(mem::take(&mut self.lexer), self.verbatim.take())
+	}
+
+	// put them back once it's done, failing if it left a quotation, comment or verbatim block open
+
+	fn emerge(&mut self, (lexer, verbatim): (Lexer, Option<(String, Vec<String>)>)) -> Result<(), MerlinError> {
+		let inner = mem::replace(&mut self.lexer, lexer);
+		let unfinished = mem::replace(&mut self.verbatim, verbatim).is_some();
+
+		// stopping early can leave anything unfinished
+
+		if !self.running {
+			return Ok(());
 		}
+
+		let e = if inner.is_pending() {
+			MerlinError::UnbalancedQuotation
+		} else if inner.is_commented() {
+			MerlinError::UnterminatedComment
+		} else if unfinished {
+			MerlinError::UnterminatedVerbatim
+		} else {
+			return Ok(());
+		};
+
+		self.fail(); // it happened at the very end
+
+		Err(e)
 	}
 
 	// return the index of the current volume, 0 if none are present
@@ -111,9 +267,67 @@ impl Plane {
 	pub fn rune(&self, nomen: &str) -> Result<String, MerlinError> {
 		Ok(self.nomens.get(nomen)
 			.ok_or(MerlinError::UnknownNomen)?
+			.atoms
 			.join(" "))
 	}
 
+	// change the working directory
+
+	pub fn wander(&mut self, path: &str) -> Result<(), MerlinError> {
+		let old = env::current_dir()
+			.or(Err(MerlinError::InvalidDirectory))?;
+
+		env::set_current_dir(&*shellexpand::tilde(path))
+			.or(Err(MerlinError::InvalidDirectory))?;
+
+		// volumes summoned with relative paths should still point to the same files
+
+		for v in self.volumes.iter_mut() {
+			v.anchor(&old);
+		}
+
+		Ok(())
+	}
+
+	// return the working directory
+
+	pub fn locus(&self) -> Result<String, MerlinError> {
+		Ok(env::current_dir()
+			.or(Err(MerlinError::InvalidDirectory))?
+			.display()
+			.to_string())
+	}
+
+	// set an environment variable, inherited by any external commands
+
+	pub fn imbue(&mut self, value: &str, name: &str) -> Result<(), MerlinError> {
+		if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+			return Err(MerlinError::InvalidSyntax);
+		}
+
+		env::set_var(name, value);
+
+		Ok(())
+	}
+
+	// unset an environment variable
+
+	pub fn purge(&mut self, name: &str) -> Result<(), MerlinError> {
+		env::var_os(name)
+			.ok_or(MerlinError::UnsetEnvironment)?;
+
+		env::remove_var(name);
+
+		Ok(())
+	}
+
+	// return the value of an environment variable
+
+	pub fn essence(&self, name: &str) -> Result<String, MerlinError> {
+		env::var(name)
+			.or(Err(MerlinError::UnsetEnvironment))
+	}
+
 	// return a list of all defined nomens to the stack
 
 	pub fn protean(&self) -> String {
@@ -0,0 +1,84 @@
+// namespaces for nomens, and spellbooks imported as modules
+
+extern crate shellexpand;
+
+use std::{env, path::PathBuf};
+use crate::error::MerlinError;
+use super::Plane;
+
+const SCHOOL_SEPARATOR: char = '.';
+const MODULE_EXTENSION: &str = "mn";
+
+// where modules are found, besides the directories in MERLIN_PATH
+
+const LIBRARY: &str = "~/.merlin/lib";
+const LIBRARY_VAR: &str = "MERLIN_PATH";
+
+impl Plane {
+	// declare the namespace nomens will be defined in, a blank name going back to the global one
+
+	pub fn school(&mut self, name: &str) {
+		self.school = match name.trim() {
+			""   => None,
+			name => Some(name.to_string()),
+		};
+	}
+
+	// qualify the name of a nomen with the current namespace
+
+	pub fn qualify(&self, name: String) -> String {
+		match &self.school {
+			Some(school) => format!("{}{}{}", school, SCHOOL_SEPARATOR, name),
+			None         => name,
+		}
+	}
+
+	// read a module, unless it has already been read
+
+	pub fn attune(&mut self, module: &str) -> Result<(), MerlinError> {
+		let path = find_module(module)?
+			.canonicalize()
+			.or(Err(MerlinError::CannotOpen))?;
+
+		if self.attuned.contains(&path) {
+			return Ok(());
+		}
+
+		// marked before reading, so modules that attune each other don't go round in circles
+
+		let faults = self.faults;
+
+		self.attuned.insert(path.clone());
+
+		let result = self.read_spellbook(&path);
+
+		// a module that didn't read cleanly can be attuned again once it's fixed
+
+		if result.is_err() || self.faults > faults {
+			self.attuned.remove(&path);
+		}
+
+		result
+	}
+}
+
+// locate a module, either by its path or by name in the library directories
+
+fn find_module(module: &str) -> Result<PathBuf, MerlinError> {
+	let path = PathBuf::from(&*shellexpand::tilde(module));
+
+	if path.is_file() {
+		return Ok(path);
+	}
+
+	let file = format!("{}.{}", module, MODULE_EXTENSION); // the module's name can have dots of its own, like a school
+
+	env::var_os(LIBRARY_VAR)
+		.map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+		.unwrap_or_default()
+		.into_iter()
+		.chain([PathBuf::from(&*shellexpand::tilde(LIBRARY))])
+		.map(|dir| dir.join(&file))
+		.find(|path| path.is_file())
+		.ok_or(MerlinError::UnknownModule)
+}
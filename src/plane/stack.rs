@@ -22,6 +22,12 @@ impl Stack {
 		self.stack.push(s)
 	}
 
+	// shrink the stack down to a certain depth
+
+	pub fn truncate(&mut self, len: usize) {
+		self.stack.truncate(len)
+	}
+
 	// grap some values from the struct
 
 	pub fn grab(&mut self, needed: usize) -> Vec<String> {
@@ -36,7 +42,7 @@ impl Stack {
 				print!("{} ", item);
 			}
 
-			print!("\n");
+			println!();
 		}
 	}
 
@@ -89,7 +95,9 @@ impl Stack {
 
 			self.stack.clear();
 
-			return Ok(self.stack.push(tethered))
+			self.stack.push(tethered);
+
+			return Ok(())
 		}
 
 		Err(MerlinError::InvalidOrNoArguments)
@@ -121,10 +129,97 @@ impl Stack {
 		a.push_str(&connector);
 		a.push_str(&b);
 
-		Ok(self.stack.push(a))
+		self.stack.push(a);
+
+		Ok(())
+	}
+
+	// copy the last item
+
+	pub fn dup(&mut self) -> Result<(), MerlinError> {
+		self.pick(1)
+	}
+
+	// copy the second to last item
+
+	pub fn over(&mut self) -> Result<(), MerlinError> {
+		self.pick(2)
+	}
+
+	// bring the third to last item to the top
+
+	pub fn rot(&mut self) -> Result<(), MerlinError> {
+		self.roll(3)
+	}
+
+	// remove the second to last item
+
+	pub fn nip(&mut self) -> Result<(), MerlinError> {
+		let i = self.index(2)?;
+		self.stack.remove(i);
+
+		Ok(())
+	}
+
+	// copy the last item below the second to last one
+
+	pub fn tuck(&mut self) -> Result<(), MerlinError> {
+		let i = self.index(2)?;
+		self.stack.insert(i, self.stack[i+1].clone());
+
+		Ok(())
+	}
+
+	// copy the nth item from the top (1 being the last item)
+
+	pub fn pick(&mut self, n: usize) -> Result<(), MerlinError> {
+		let i = self.index(n)?;
+		self.stack.push(self.stack[i].clone());
+
+		Ok(())
+	}
+
+	// move the nth item from the top to the top
+
+	pub fn roll(&mut self, n: usize) -> Result<(), MerlinError> {
+		let i = self.index(n)?;
+		let item = self.stack.remove(i);
+		self.stack.push(item);
+
+		Ok(())
+	}
+
+	// remove the last n items
+
+	pub fn drop(&mut self, n: usize) -> Result<(), MerlinError> {
+		if n > self.len() {
+			return Err(MerlinError::OutOfBounds);
+		}
+
+		self.stack.truncate(self.len() - n);
+
+		Ok(())
+	}
+
+	// print the nth item from the top, leaving the stack alone
+
+	pub fn peek(&self, n: usize) -> Result<(), MerlinError> {
+		print!("{}", self.stack[self.index(n)?]);
+
+		Ok(())
+	}
+
+	// convert a position from the top of the stack into an index
+
+	fn index(&self, n: usize) -> Result<usize, MerlinError> {
+		if n == 0 || n > self.len() {
+			return Err(MerlinError::OutOfBounds);
+		}
+
+		Ok(self.len() - n)
 	}
 
-	fn pop(&mut self) -> Result<String, MerlinError> {
+	pub fn pop(&mut self) -> Result<String, MerlinError> {
 		self.stack.pop()
 			.ok_or(MerlinError::InvalidOrNoArguments)
 	}
@@ -0,0 +1,79 @@
+// commands for juggling multiple named stacks
+
+use std::mem;
+use crate::error::MerlinError;
+use super::{Plane, stack::Stack};
+
+pub const PRIME_STACK: &str = "prime";
+
+impl Plane {
+	// switch to another stack, creating it if it doesn't exist
+
+	pub fn delve(&mut self, name: &str) {
+		if name != self.stack_name {
+			let stack = self.stacks.remove(name).unwrap_or_default();
+			let old = mem::replace(&mut self.stack, stack);
+
+			self.stacks.insert(mem::replace(&mut self.stack_name, name.to_string()), old);
+		}
+	}
+
+	// push an atom onto another stack
+
+	pub fn send(&mut self, atom: String, name: &str) {
+		self.named_stack(name).push(atom);
+	}
+
+	// pop an atom off of another stack
+
+	pub fn fetch(&mut self, name: &str) -> Result<String, MerlinError> {
+		if name != self.stack_name && !self.stacks.contains_key(name) {
+			return Err(MerlinError::UnknownStack);
+		}
+
+		self.named_stack(name).pop()
+			.or(Err(MerlinError::OutOfBounds))
+	}
+
+	// return a sorted list of all of the stacks
+
+	pub fn stacks(&self) -> String {
+		let mut names: Vec<&str> = self.stacks.keys()
+			.map(String::as_str)
+			.collect();
+
+		names.push(&self.stack_name);
+		names.sort_unstable();
+
+		names.join("\n")
+	}
+
+	// remember how deep the stack is, and which stack it was
+
+	pub fn mark(&mut self) {
+		self.marks.push((self.stack_name.clone(), self.stack.len()));
+	}
+
+	// throw away everything pushed since the last mark, on the stack it was made on
+
+	pub fn rewind(&mut self) -> Result<(), MerlinError> {
+		let (name, depth) = self.marks.pop()
+			.ok_or(MerlinError::NoMark)?;
+
+		if name != self.stack_name && !self.stacks.contains_key(&name) {
+			return Err(MerlinError::UnknownStack);
+		}
+
+		self.named_stack(&name).truncate(depth);
+
+		Ok(())
+	}
+
+	fn named_stack(&mut self, name: &str) -> &mut Stack {
+		if name == self.stack_name {
+			&mut self.stack
+		} else {
+			self.stacks.entry(name.to_string()).or_default()
+		}
+	}
+}
@@ -0,0 +1,46 @@
+// stopping early, and the status merlin exits with
+
+use crate::error::{MerlinError, EXIT_SUCCESS, EXIT_UNSAVED};
+use super::Plane;
+
+impl Plane {
+	// stop at the first error that isn't caught
+
+	pub fn strict(&mut self) {
+		self.strict = true;
+	}
+
+	pub fn is_running(&self) -> bool {
+		self.running
+	}
+
+	// quit with a certain exit status, which the OS only keeps the lowest byte of
+
+	pub fn exit(&mut self, status: u8) {
+		self.exit_status = Some(i32::from(status));
+		self.running = false;
+	}
+
+	// remember the first error that wasn't caught
+
+	pub fn record(&mut self, e: &MerlinError) {
+		self.error_status.get_or_insert(e.status());
+		self.faults += 1;
+	}
+
+	// the status to exit with: the one given to ;exit, the first error, or whether anything is left unsaved.
+	// someone at the repl has already seen their errors and chosen what to save, so only ;exit counts there
+
+	pub fn status(&self) -> i32 {
+		if self.interactive {
+			return self.exit_status.unwrap_or(EXIT_SUCCESS);
+		}
+
+		self.exit_status
+			.or(self.error_status)
+			.unwrap_or_else(|| match self.volumes.iter().all(|v| v.written) {
+				true  => EXIT_SUCCESS,
+				false => EXIT_UNSAVED,
+			})
+	}
+}
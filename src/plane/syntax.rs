@@ -0,0 +1,25 @@
+// highlighting atoms with the syntax of the focused volume
+
+use crate::terminal::{self, STDOUT};
+use super::Plane;
+
+impl Plane {
+	// color an atom (say, from ;peer) like the focused volume, as long as we're printing to a terminal
+
+	pub fn glow(&self, atom: &str) -> String {
+		if !terminal::is_terminal(STDOUT) {
+			return atom.to_string();
+		}
+
+		let syntax = self.volumes.get(self.current_volume)
+			.and_then(|vol| self.highlighter.syntax(vol));
+
+		match syntax {
+			Some(s) => atom.split('\n')
+				.map(|line| s.paint(line))
+				.collect::<Vec<_>>()
+				.join("\n"),
+			None    => atom.to_string(),
+		}
+	}
+}
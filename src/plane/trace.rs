@@ -0,0 +1,71 @@
+// keeping track of where we are, so errors can say where they happened
+
+use crate::error::{MerlinError, Fault, Site};
+use super::Plane;
+
+impl Plane {
+	// print an error that nothing caught, along with where it happened, and run the error nomen
+
+	pub fn report(&mut self, e: &MerlinError) {
+		let trace = self.failure.take()
+			.unwrap_or_else(|| self.trace.clone());
+
+		eprintln!("{}", Fault { error: e, trace: &trace });
+
+		self.lament = e.message();
+
+		if let Some(v) = &mut self.visual {
+			v.message = Some(e.message());
+		}
+
+		self.record(e);
+
+		// an error inside of the error nomen shouldn't set it off again
+
+		if !self.lamenting {
+			self.lamenting = true;
+			self.atom_push(";error-nomen");
+			self.lamenting = false;
+		}
+
+		// in strict mode we stop, once the error nomen has had its say
+
+		if self.strict {
+			self.running = false;
+		}
+	}
+
+	// remember where an error happened, before we unwind out of the nomens it happened in
+
+	pub fn fail(&mut self) {
+		if self.failure.is_none() {
+			self.failure = Some(self.trace.clone());
+		}
+	}
+
+	// start parsing something new: a file, a merlin block or a nomen
+
+	pub fn enter(&mut self, name: String) {
+		self.trace.push(Site { name, atom: 0 });
+	}
+
+	pub fn leave(&mut self) {
+		self.trace.pop();
+	}
+
+	// move on to the next line of whatever we are parsing
+
+	pub fn relabel(&mut self, name: String) {
+		if let Some(site) = self.trace.last_mut() {
+			*site = Site { name, atom: 0 };
+		}
+	}
+
+	// move on to the next atom
+
+	pub fn step(&mut self) {
+		if let Some(site) = self.trace.last_mut() {
+			site.atom += 1;
+		}
+	}
+}
@@ -0,0 +1,476 @@
+// a full screen view of the focused volume, with a command line at the bottom
+
+use std::{io::{self, Write}, iter};
+use crate::{error::MerlinError, editor::{Editor, Helper, Input}, terminal::{self, STDIN, STDOUT}, util};
+use crate::{volume::Volume, highlight::{self, Syntax}};
+use super::{Plane, Vision};
+
+const ALTERNATE_SCREEN_ON: &str = "\x1b[?1049h";
+const ALTERNATE_SCREEN_OFF: &str = "\x1b[?1049l";
+
+const TAB_WIDTH: usize = 8;
+
+// a pane, showing a volume with its own scroll position
+
+#[derive(Clone, Default)]
+struct Pane {
+	volume: usize,
+	top: usize,          // the first line shown
+	left: usize,         // the first column shown
+	spot: Option<usize>, // where the cursor was when we last drew, we only follow it when it moves
+}
+
+// how the panes are laid out: all stacked on top of each other, or all side by side
+
+#[derive(Copy, Clone, Default)]
+pub enum Split {
+	#[default]
+	Stacked,
+	Beside,
+}
+
+// where on the screen a pane is drawn
+
+struct Rect {
+	row: usize,
+	col: usize,
+	height: usize,
+	width: usize,
+}
+
+// the alternate screen, left when dropped so that even a panic gives the user their terminal back
+
+struct AlternateScreen;
+
+impl AlternateScreen {
+	fn enter() -> Self {
+		write_screen(ALTERNATE_SCREEN_ON);
+		Self
+	}
+}
+
+impl Drop for AlternateScreen {
+	fn drop(&mut self) {
+		write_screen(ALTERNATE_SCREEN_OFF);
+	}
+}
+
+// the state of the full screen view
+
+pub struct Visual {
+	pub size: (usize, usize),    // rows and columns of the terminal when we last drew
+	panes: Vec<Pane>,
+	focused: usize,
+	split: Split,
+	pub message: Option<String>, // the last error, shown in the status line
+}
+
+impl Visual {
+	fn new(volume: usize) -> Self {
+		Self {
+			size: (0, 0),
+			panes: vec![Pane { volume, ..Pane::default() }],
+			focused: 0,
+			split: Split::Stacked,
+			message: None,
+		}
+	}
+}
+
+impl Plane {
+	// enter visual mode, or leave it if we're already in it
+
+	pub fn gaze(&mut self) -> Result<(), MerlinError> {
+		if self.visual.is_some() {
+			self.visual = None;
+			return Ok(());
+		}
+
+		if !terminal::is_terminal(STDIN) || !terminal::is_terminal(STDOUT) {
+			return Err(MerlinError::NotATerminal);
+		}
+
+		self.visual = Some(Visual::new(self.current_volume));
+
+		let _screen = AlternateScreen::enter();
+
+		let mut editor = Editor::new();
+
+		while self.running && self.visual.is_some() {
+			Helper::prompt(self); // draw the screen, then the prompt
+
+			let input = editor.read_line(self);
+
+			if let Some(v) = &mut self.visual {
+				v.message = None;
+			}
+
+			match input {
+				Some(Input::Line(line))  => {
+					if let Vision::Atom = self.vision {
+						editor.remember(&line);
+					}
+
+					self.parse_line(&line);
+				}
+				Some(Input::Paste(text)) => self.parse_paste(&text),
+				None                     => break,
+			}
+		}
+
+		self.visual = None;
+
+		Ok(())
+	}
+
+	// draw each pane and its status line, leaving the cursor on the command line
+
+	pub fn draw(&mut self) {
+		let (rows, cols) = screen_size();
+		let area = rows.saturating_sub(1); // everything but the command line
+
+		let visual = match &mut self.visual {
+			Some(v) => v,
+			None    => return,
+		};
+
+		visual.size = (rows, cols);
+
+		// the focused pane always shows the focused volume
+
+		let focused = visual.focused;
+		visual.panes[focused].volume = self.current_volume;
+
+		let mode = match self.vision {
+			Vision::Atom   => "atom",
+			Vision::Scribe => "scribe",
+		};
+
+		let mut extra = format!("  {}  {} atoms", mode, self.stack.len());
+
+		if let Some(m) = &visual.message {
+			extra.push_str(&format!("  {} {}", util::ERROR_PREFIX, m));
+		}
+
+		let mut screen = String::from("\x1b[?25l"); // hide the cursor while we draw
+		let n = visual.panes.len();
+
+		for (i, pane) in visual.panes.iter_mut().enumerate() {
+			let rect = match visual.split {
+				Split::Stacked => Rect {
+					row: area * i / n,
+					col: 0,
+					height: area * (i + 1) / n - area * i / n,
+					width: cols,
+				},
+				Split::Beside  => {
+					let (start, end) = (cols * i / n, cols * (i + 1) / n);
+
+					// leave a column between panes for a separator
+
+					if i + 1 < n {
+						for row in 0..area {
+							screen.push_str(&format!("\x1b[{};{}H\u{2502}", row + 1, end));
+						}
+					}
+
+					Rect { row: 0, col: start, height: area, width: (end - start).saturating_sub((i + 1 < n) as usize) }
+				}
+			};
+
+			let status = match i == focused {
+				true  => extra.as_str(),
+				false => "",
+			};
+
+			let vol = self.volumes.get(pane.volume);
+			let syntax = vol.and_then(|v| self.highlighter.syntax(v));
+
+			screen.push_str(&draw_pane(pane, &rect, vol, syntax, status, i == focused));
+		}
+
+		screen.push_str(&format!("\x1b[{};1H\x1b[K\x1b[?25h", rows));
+
+		write_screen(&screen);
+	}
+
+	// scroll the focused pane by a number of pages
+
+	pub fn page(&mut self, pages: isize) {
+		let span = match self.volumes.get(self.current_volume) {
+			Some(vol) => vol.span(),
+			None      => return,
+		};
+
+		if let Some(visual) = &mut self.visual {
+			let height = (visual.size.0.saturating_sub(1) / visual.panes.len()).saturating_sub(1).max(1) as isize;
+			let pane = &mut visual.panes[visual.focused];
+
+			pane.top = (pane.top as isize + pages * height).clamp(0, span as isize - 1) as usize;
+		}
+	}
+
+	// split the focused pane in two, both showing the same volume
+
+	pub fn cleave(&mut self, split: Split) -> Result<(), MerlinError> {
+		let visual = self.visual.as_mut()
+			.ok_or(MerlinError::NotGazing)?;
+
+		let pane = visual.panes[visual.focused].clone();
+
+		visual.split = split;
+		visual.focused += 1;
+		visual.panes.insert(visual.focused, pane);
+
+		Ok(())
+	}
+
+	// close the focused pane
+
+	pub fn mend(&mut self) -> Result<(), MerlinError> {
+		let visual = self.visual.as_mut()
+			.ok_or(MerlinError::NotGazing)?;
+
+		if visual.panes.len() == 1 {
+			return Err(MerlinError::LastPane);
+		}
+
+		visual.panes.remove(visual.focused);
+		visual.focused = visual.focused.saturating_sub(1);
+
+		// the pane may have been showing a volume that was shelved
+
+		self.current_volume = visual.panes[visual.focused].volume
+			.min(self.volumes.len().saturating_sub(1));
+
+		Ok(())
+	}
+
+	// move the focus to another pane, relative to the focused one
+
+	pub fn glance(&mut self, offset: isize) -> Result<(), MerlinError> {
+		let visual = self.visual.as_mut()
+			.ok_or(MerlinError::NotGazing)?;
+
+		visual.focused = (visual.focused as isize + offset).rem_euclid(visual.panes.len() as isize) as usize;
+
+		self.current_volume = visual.panes[visual.focused].volume
+			.min(self.volumes.len().saturating_sub(1));
+
+		Ok(())
+	}
+
+	// keep the panes showing the same volumes when one is opened or closed before them
+
+	pub fn reindex_panes(&mut self, index: usize, inserted: bool) {
+		if let Some(visual) = &mut self.visual {
+			for pane in visual.panes.iter_mut() {
+				if inserted && pane.volume >= index {
+					pane.volume += 1;
+				} else if !inserted && pane.volume > index {
+					pane.volume -= 1;
+				}
+			}
+		}
+	}
+}
+
+// the line editor draws the screen before each prompt, and leaves it when we're suspended
+
+impl Helper for Plane {
+	fn prompt(&mut self) {
+		if self.visual.is_some() {
+			self.draw();
+		}
+
+		Plane::prompt(self);
+		util::err_msg(io::stdout().flush(), "unable to flush stdout");
+	}
+
+	fn complete(&self, before: &str, after: &str) -> (usize, Vec<String>) {
+		self.completions(before, after)
+	}
+
+	fn scroll(&mut self, pages: isize) {
+		self.page(pages);
+	}
+
+	fn stale(&mut self) -> bool {
+		self.visual.as_ref().is_some_and(|v| v.size != screen_size())
+	}
+
+	fn suspend(&mut self) {
+		if self.visual.is_some() {
+			write_screen(ALTERNATE_SCREEN_OFF);
+		}
+	}
+
+	fn resume(&mut self) {
+		if self.visual.is_some() {
+			write_screen(ALTERNATE_SCREEN_ON);
+		}
+	}
+}
+
+// draw a single pane, with its status line at the bottom
+
+fn draw_pane(pane: &mut Pane, rect: &Rect, vol: Option<&Volume>, syntax: Option<&Syntax>, extra: &str, focused: bool) -> String {
+	let mut screen = String::new();
+	let height = rect.height.saturating_sub(1);
+
+	let status = match vol {
+		Some(vol) => {
+			let spot = vol.spot() - 1;
+			let line = vol.line_text(spot).unwrap_or_default();
+			let column = column(&line, vol.pin() - 1);
+
+			// follow the cursor when it moves, otherwise stay wherever we've scrolled to
+
+			if pane.spot != Some(spot) {
+				if spot < pane.top {
+					pane.top = spot;
+				} else if spot >= pane.top + height {
+					pane.top = spot + 1 - height;
+				}
+
+				pane.spot = Some(spot);
+			}
+
+			if column < pane.left {
+				pane.left = column;
+			} else if column >= pane.left + rect.width {
+				pane.left = column + 1 - rect.width;
+			}
+
+			for row in 0..height {
+				let n = pane.top + row;
+				let cursor = (focused && n == spot).then(|| vol.pin() - 1);
+
+				screen.push_str(&format!("\x1b[{};{}H", rect.row + row + 1, rect.col + 1));
+
+				match vol.line_text(n) {
+					Some(text) => {
+						let styles = syntax.map(|s| s.styles(&text)).unwrap_or_default();
+
+						screen.push_str(&render(&text, &styles, pane.left, rect.width, cursor));
+					}
+					None       => screen.push_str(&render("~", &[], 0, rect.width, None)),
+				}
+			}
+
+			let name = vol.name()
+				.map(|n| n.display().to_string())
+				.unwrap_or_else(|| String::from("[unnamed]"));
+
+			format!(" {} {}  {}:{}{}", name, vol.carved(), vol.spot(), vol.pin(), extra)
+		}
+		None      => {
+			for row in 0..height {
+				screen.push_str(&format!("\x1b[{};{}H{}", rect.row + row + 1, rect.col + 1, render("~", &[], 0, rect.width, None)));
+			}
+
+			format!(" no open volumes{}", extra)
+		}
+	};
+
+	let status: String = status.chars()
+		.chain(iter::repeat(' '))
+		.take(rect.width)
+		.collect();
+
+	// the focused pane's status line stands out
+
+	let style = match focused {
+		true  => "\x1b[1;7m",
+		false => "\x1b[7m",
+	};
+
+	screen.push_str(&format!("\x1b[{};{}H{}{}\x1b[0m", rect.row + height + 1, rect.col + 1, style, status));
+
+	screen
+}
+
+// the size of the terminal in rows and columns
+
+pub fn screen_size() -> (usize, usize) {
+	match terminal::window_size(STDOUT) {
+		Some(size) => (size.ws_row as usize, size.ws_col as usize),
+		None       => (24, 80),
+	}
+}
+
+// write to the screen right away
+
+pub fn write_screen(s: &str) {
+	let mut stdout = io::stdout();
+
+	util::err_msg(stdout.write_all(s.as_bytes()), "unable to write to the screen");
+	util::err_msg(stdout.flush(), "unable to flush stdout");
+}
+
+// the column a character of a line is drawn in, with tabs expanded
+
+fn column(line: &str, index: usize) -> usize {
+	line.chars()
+		.take(index)
+		.fold(0, |col, c| match c {
+			'\t' => col + TAB_WIDTH - col % TAB_WIDTH,
+			_    => col + 1,
+		})
+}
+
+// draw the visible part of a line in the style of each character, padded out to the width of its pane, showing the cursor if it's on this line
+
+fn render(line: &str, styles: &[Option<&str>], left: usize, width: usize, cursor: Option<usize>) -> String {
+	let mut out = String::new();
+	let mut col = 0;
+	let mut current = None;
+
+	let len = line.chars().count();
+
+	// an extra space lets the cursor sit at the end of the line
+
+	for (i, c) in line.chars().chain(iter::once(' ')).enumerate() {
+		if i == len && cursor != Some(i) {
+			break;
+		}
+
+		let cells = match c {
+			'\t' => TAB_WIDTH - col % TAB_WIDTH,
+			_    => 1,
+		};
+
+		// control characters would mess with the terminal
+
+		let shown = match c {
+			'\t'                => ' ',
+			c if c.is_control() => '?',
+			c                   => c,
+		};
+
+		let style = styles.get(i).copied().flatten();
+
+		for cell in 0..cells {
+			if col >= left && col < left + width {
+				out.push_str(&highlight::restyle(&mut current, style));
+
+				if cursor == Some(i) && cell == 0 {
+					out.push_str(&format!("\x1b[7m{}\x1b[27m", shown));
+				} else {
+					out.push(shown);
+				}
+			}
+
+			col += 1;
+		}
+	}
+
+	out.push_str(&highlight::restyle(&mut current, None));
+
+	// clear whatever was drawn here before
+
+	let drawn = col.saturating_sub(left).min(width);
+	out.extend(iter::repeat_n(' ', width - drawn));
+
+	out
+}
@@ -0,0 +1,61 @@
+// restricted mode, guarding against notation from untrusted sources
+
+use std::{env, path::{Path, PathBuf}};
+use crate::error::MerlinError;
+use super::Plane;
+
+impl Plane {
+	// enter restricted mode, there is no way back
+
+	pub fn restrict(&mut self) {
+		self.restricted = true;
+	}
+
+	// only allow writing files inside of a certain directory while restricted
+
+	pub fn allow(&mut self, dir: &str) {
+		let path = PathBuf::from(&*shellexpand::tilde(dir));
+
+		self.sanctums.push(path.canonicalize().unwrap_or(path));
+	}
+
+	// make sure we are allowed to run external commands
+
+	pub fn ward_external(&self) -> Result<(), MerlinError> {
+		if self.restricted {
+			return Err(MerlinError::ExternalForbidden);
+		}
+
+		Ok(())
+	}
+
+	// make sure we are allowed to write to a path
+
+	pub fn ward_write(&self, path: &Path) -> Result<(), MerlinError> {
+		if !self.restricted || self.sanctums.is_empty() {
+			return Ok(());
+		}
+
+		let target = resolve(path).ok_or(MerlinError::WriteForbidden)?;
+
+		if self.sanctums.iter().any(|s| target.starts_with(s)) {
+			Ok(())
+		} else {
+			Err(MerlinError::WriteForbidden)
+		}
+	}
+}
+
+// find where a (possibly nonexistent) file really lives, following any symlinks in its directory
+
+fn resolve(path: &Path) -> Option<PathBuf> {
+	let absolute = env::current_dir().ok()?.join(path);
+
+	if let Ok(p) = absolute.canonicalize() {
+		return Some(p);
+	}
+
+	let file = absolute.file_name()?;
+
+	Some(absolute.parent()?.canonicalize().ok()?.join(file))
+}
@@ -0,0 +1,91 @@
+// talking to the terminal directly
+
+use std::{mem, os::unix::io::RawFd};
+
+use nix::{
+	libc,
+	pty::Winsize,
+	sys::{signal::{self, Signal}, termios::{self, SetArg, Termios}},
+	unistd,
+};
+
+pub const STDIN: RawFd = libc::STDIN_FILENO;
+pub const STDOUT: RawFd = libc::STDOUT_FILENO;
+
+// check if a file descriptor is connected to a terminal
+
+pub fn is_terminal(fd: RawFd) -> bool {
+	unistd::isatty(fd).unwrap_or(false)
+}
+
+// get the size of the terminal connected to a file descriptor
+
+pub fn window_size(fd: RawFd) -> Option<Winsize> {
+	let mut size: Winsize = unsafe { mem::zeroed() };
+
+	match unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) } {
+		0 => Some(size),
+		_ => None,
+	}
+}
+
+// set the size of the terminal connected to a file descriptor
+
+pub fn set_window_size(fd: RawFd, size: &Winsize) {
+	unsafe { libc::ioctl(fd, libc::TIOCSWINSZ, size) };
+}
+
+// write an entire buffer to a file descriptor
+
+pub fn write_all(fd: RawFd, mut buf: &[u8]) -> nix::Result<()> {
+	while !buf.is_empty() {
+		match unistd::write(fd, buf) {
+			Ok(n)                  => buf = &buf[n..],
+			Err(nix::Error::EINTR) => (),
+			Err(e)                 => return Err(e),
+		}
+	}
+
+	Ok(())
+}
+
+// a terminal in raw mode, its original settings are restored when dropped
+
+pub struct RawMode {
+	fd: RawFd,
+	original: Termios,
+}
+
+impl RawMode {
+	// put the terminal into raw mode, if we are connected to one
+
+	pub fn enter(fd: RawFd) -> Option<Self> {
+		if !is_terminal(fd) {
+			return None;
+		}
+
+		let original = termios::tcgetattr(fd).ok()?;
+		let mut raw = original.clone();
+
+		termios::cfmakeraw(&mut raw);
+		termios::tcsetattr(fd, SetArg::TCSADRAIN, &raw).ok()?;
+
+		Some(Self { fd, original })
+	}
+
+	// stop ourselves (like Ctrl-Z normally would), with the terminal the way we found it until we're continued
+
+	pub fn suspend(&self) {
+		if let Ok(raw) = termios::tcgetattr(self.fd) {
+			let _ = termios::tcsetattr(self.fd, SetArg::TCSADRAIN, &self.original);
+			let _ = signal::raise(Signal::SIGTSTP);
+			let _ = termios::tcsetattr(self.fd, SetArg::TCSADRAIN, &raw);
+		}
+	}
+}
+
+impl Drop for RawMode {
+	fn drop(&mut self) {
+		let _ = termios::tcsetattr(self.fd, SetArg::TCSADRAIN, &self.original);
+	}
+}
@@ -4,7 +4,7 @@ mod vol_commands;
 
 use std::{
 	fs::File,
-	path::PathBuf,
+	path::{Path, PathBuf},
 	io::{BufRead, BufReader},
 	iter::FromIterator,
 };
@@ -35,6 +35,7 @@ pub struct Volume {
 	cursor: usize,
 
 	pub written: bool,
+	pub syntax: Option<String>, // the syntax to highlight with, rather than guessing it
 }
 
 impl Volume {
@@ -62,7 +63,8 @@ impl Volume {
 			buffer: buff,
 			line: 0,
 			cursor: 0,
-			written: false
+			written: false,
+			syntax: None,
 		}
 	}
 
@@ -100,9 +102,26 @@ impl Volume {
 			buffer: buff,
 			line: 0,
 			cursor: 0,
-			written: w
+			written: w,
+			syntax: None,
 		})
 	}
+
+	// return the path of the volume, if it has one
+
+	pub fn name(&self) -> Option<&Path> {
+		self.name.as_deref()
+	}
+
+	// resolve a relative name against a directory, so the volume survives a change of directory
+
+	pub fn anchor(&mut self, base: &Path) {
+		if let Some(p) = &self.name {
+			if p.is_relative() {
+				self.name = Some(base.join(p));
+			}
+		}
+	}
 }
 
 // display our volume
@@ -168,7 +168,7 @@ impl Volume {
 	pub fn carve(&mut self) -> Result<(), MerlinError> {
 		match &self.name {
 			Some(name) => {
-				fs::write(&name, &(self.buff_to_string(1, self.buffer.len()) + "\n")
+				fs::write(name, (self.text() + "\n")
 					.as_bytes()).or(Err(MerlinError::CreationOrWriteFailed))?;
 
 				self.written = true;
@@ -178,6 +178,18 @@ impl Volume {
 		}
 	}
 
+	// return the text of a line, counting from 0
+
+	pub fn line_text(&self, index: usize) -> Option<String> {
+		self.buffer.get(index).map(|l| l.iter().collect())
+	}
+
+	// return the whole buffer as text
+
+	pub fn text(&self) -> String {
+		self.buff_to_string(1, self.buffer.len())
+	}
+
 	// return a character based on if the buffer is saved or not
 
 	pub fn carved(&self) -> String {
@@ -262,9 +274,7 @@ fn move_respect_bounds(curr: usize, len: usize, n: isize) -> usize {
 fn goto_respect_bounds(len: usize, n: usize) -> usize {
 	if n > len {
 		len
-	} else if n < 1 {
-		0
 	} else {
-		n-1
+		n.saturating_sub(1)
 	}
 }